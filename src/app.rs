@@ -0,0 +1,192 @@
+use crate::{keymap::KeyBinding, *};
+use std::{
+    io::{stdin, stdout},
+    panic::{set_hook, take_hook, PanicHookInfo},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+///Longest single wait, so `App::should_quit` set from elsewhere is noticed.
+const IDLE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// The state of a program driven by `Runner`.
+///
+/// `update` and `tick` return true when something changed, the screen is only redrawn then.
+///
+/// ```no_run
+/// use winter::{keymap::KeyBinding, *};
+///
+/// struct Counter(usize);
+///
+/// impl App for Counter {
+///     fn update(&mut self, event: Event, _: KeyState) -> bool {
+///         let Event::Key(key) = event else {
+///             return false;
+///         };
+///         match key.code {
+///             KeyCode::Up => self.0 += 1,
+///             KeyCode::Down => self.0 = self.0.saturating_sub(1),
+///             _ => return false,
+///         }
+///         true
+///     }
+///     fn draw(&mut self, buf: &mut Buffer) {
+///         lines!(self.0.to_string()).draw(buf.area, buf);
+///     }
+/// }
+///
+/// let escape = KeyBinding::new(KeyCode::Escape, KeyState(0));
+/// runner().quit_key(escape).run(&mut Counter(0))?;
+/// # Ok::<(), WinterError>(())
+/// ```
+pub trait App {
+    fn update(&mut self, event: Event, state: KeyState) -> bool;
+    fn draw(&mut self, buf: &mut Buffer);
+    /// Where to show the cursor after drawing, see `Winter::set_cursor`.
+    fn cursor(&self) -> Option<(u16, u16)> {
+        None
+    }
+    /// Called every `Runner::tick_rate`.
+    fn tick(&mut self) -> bool {
+        false
+    }
+    fn should_quit(&self) -> bool {
+        false
+    }
+}
+
+pub fn runner() -> Runner {
+    Runner {
+        tick_rate: None,
+        max_fps: 60,
+        quit_keys: vec![KeyBinding::new(KeyCode::Char('c'), KeyState(CONTROL))],
+        options: WinterOptions::new(),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Runner {
+    pub tick_rate: Option<Duration>,
+    /// Redraws are spaced at least `1 / max_fps` apart, 0 means no limit.
+    pub max_fps: u32,
+    /// Ctrl+C by default.
+    pub quit_keys: Vec<KeyBinding>,
+    pub options: WinterOptions,
+}
+
+impl Default for Runner {
+    fn default() -> Self {
+        runner()
+    }
+}
+
+impl Runner {
+    pub fn tick_rate(mut self, tick_rate: Duration) -> Self {
+        self.tick_rate = Some(tick_rate);
+        self
+    }
+    pub fn max_fps(mut self, max_fps: u32) -> Self {
+        self.max_fps = max_fps;
+        self
+    }
+    pub fn quit_key(mut self, key: KeyBinding) -> Self {
+        self.quit_keys.push(key);
+        self
+    }
+    /// Replace the default quit keys, pass an empty slice to quit only through `App::should_quit`.
+    pub fn quit_keys(mut self, quit_keys: &[KeyBinding]) -> Self {
+        self.quit_keys = quit_keys.to_vec();
+        self
+    }
+    pub fn options(mut self, options: WinterOptions) -> Self {
+        self.options = options;
+        self
+    }
+    /// Run `app` on the console until it quits.
+    ///
+    /// A panic hook restores the terminal before the panic message is printed,
+    /// otherwise it would be lost on the alternate screen.
+    pub fn run<A: App>(&self, app: &mut A) -> Result<(), WinterError> {
+        let mut winter = Winter::with_options(self.options)?;
+
+        let original: Arc<dyn Fn(&PanicHookInfo<'_>) + Send + Sync> = take_hook().into();
+        let hook = original.clone();
+        set_hook(Box::new(move |info| {
+            uninit(&mut stdout(), &mut stdin());
+            hook(info);
+        }));
+
+        let result = self.run_with(&mut winter, app);
+
+        let _ = take_hook();
+        set_hook(Box::new(move |info| original(info)));
+        result
+    }
+    /// Run `app` on an existing `Winter`, e.g. one with a `TestBackend`.
+    pub fn run_with<A: App, B: Backend>(
+        &self,
+        winter: &mut Winter<B>,
+        app: &mut A,
+    ) -> Result<(), WinterError> {
+        let frame_time = match self.max_fps {
+            0 => Duration::ZERO,
+            fps => Duration::from_secs(1) / fps,
+        };
+        let mut last_tick = Instant::now();
+        let mut last_frame: Option<Instant> = None;
+        let mut dirty = true;
+
+        loop {
+            if app.should_quit() {
+                return Ok(());
+            }
+
+            let frame_ready = last_frame.is_none_or(|frame| frame.elapsed() >= frame_time);
+            if dirty && frame_ready {
+                let viewport = winter.viewport;
+                app.draw(winter.buffer());
+                winter.set_cursor(app.cursor());
+                winter.draw()?;
+                last_frame = Some(Instant::now());
+                //The frame was drawn at the old size, it has to be drawn again.
+                dirty = winter.viewport != viewport;
+            }
+
+            //Sleep until the next tick, or the next frame if one is waiting.
+            let mut timeout = IDLE_TIMEOUT;
+            if let Some(tick_rate) = self.tick_rate {
+                timeout = timeout.min(tick_rate.saturating_sub(last_tick.elapsed()));
+            }
+            if let (true, Some(frame)) = (dirty, last_frame) {
+                timeout = timeout.min(frame_time.saturating_sub(frame.elapsed()));
+            }
+            if let Some(until) = winter.flash_until {
+                timeout = timeout.min(until.saturating_duration_since(Instant::now()));
+            }
+
+            if let Some((event, state)) = winter.poll_timeout(timeout)? {
+                if self.quit_keys.iter().any(|key| key.matches(&event, state)) {
+                    return Ok(());
+                }
+                if matches!(event, Event::Resize(..) | Event::Resume) {
+                    dirty = true;
+                }
+                dirty |= app.update(event, state);
+            }
+
+            //The visual bell is over, the next frame clears it.
+            if winter
+                .flash_until
+                .is_some_and(|until| Instant::now() >= until)
+            {
+                dirty = true;
+            }
+            if let Some(tick_rate) = self.tick_rate {
+                if last_tick.elapsed() >= tick_rate {
+                    last_tick = Instant::now();
+                    dirty |= app.tick();
+                }
+            }
+        }
+    }
+}
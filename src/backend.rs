@@ -0,0 +1,897 @@
+use crate::{
+    buffer::{Buffer, Scroll},
+    layout::Rect,
+    *,
+};
+use std::{
+    collections::VecDeque,
+    io::{stdin, stdout, IsTerminal, Stdin, Stdout, Write},
+    time::{Duration, Instant},
+};
+
+#[cfg(windows)]
+use std::os::windows::io::AsRawHandle;
+
+/// Everything `Winter` needs from a terminal.
+///
+/// Escape sequences are written through `Write`, the default methods
+/// use the same helpers as the rest of the crate.
+pub trait Backend: Write + Sized {
+    /// Size of the visible window in cells.
+    fn size(&mut self) -> Result<(u16, u16), WinterError>;
+    /// Platform setup that is always needed, e.g. enabling escape codes.
+    fn init(&mut self) -> Result<(), WinterError>;
+    /// Undo everything `init` did.
+    fn uninit(&mut self) -> Result<(), WinterError>;
+    /// Wait at most `timeout` for the next event.
+    fn poll(&mut self, timeout: Duration) -> Result<Option<(Event, KeyState)>, WinterError>;
+    /// Disable echo and line buffering so keys are read as they are pressed.
+    fn set_raw_mode(&mut self, enabled: bool) -> Result<(), WinterError>;
+    /// Zero-based position of the cursor inside the window.
+    fn cursor_position(&mut self) -> Result<(u16, u16), WinterError>;
+
+    /// Stop the process until the shell continues it, e.g. after Ctrl+Z.
+    /// Does nothing where job control doesn't exist.
+    fn stop_process(&mut self) -> Result<(), WinterError> {
+        Ok(())
+    }
+    /// Catch job control signals so `poll` reports them as `Event::Suspend` and `Event::Resume`,
+    /// or give them back their default actions. Does nothing where job control doesn't exist.
+    fn set_job_control(&mut self, _enabled: bool) -> Result<(), WinterError> {
+        Ok(())
+    }
+    /// Whether the terminal supports synchronized output (mode 2026).
+    fn synchronized_output(&mut self) -> Result<bool, WinterError> {
+        Ok(false)
+    }
+    /// Bytes written but not flushed yet, `RenderStats::bytes` is read from this
+    /// right before a frame is flushed. 0 if nothing is held back.
+    fn buffered_len(&self) -> usize {
+        0
+    }
+    fn set_mouse_capture(&mut self, enabled: bool) -> Result<(), WinterError> {
+        if enabled {
+            enable_mouse_capture(self);
+        } else {
+            disable_mouse_capture(self);
+        }
+        Ok(())
+    }
+    fn set_bracketed_paste(&mut self, enabled: bool) -> Result<(), WinterError> {
+        if enabled {
+            enable_bracketed_paste(self);
+        } else {
+            disable_bracketed_paste(self);
+        }
+        Ok(())
+    }
+    fn set_focus_reporting(&mut self, enabled: bool) -> Result<(), WinterError> {
+        if enabled {
+            enable_focus_reporting(self);
+        } else {
+            disable_focus_reporting(self);
+        }
+        Ok(())
+    }
+    fn set_kitty_keyboard(&mut self, enabled: bool) -> Result<(), WinterError> {
+        if enabled {
+            push_keyboard_flags(self, KEYBOARD_FLAGS);
+        } else {
+            pop_keyboard_flags(self);
+        }
+        Ok(())
+    }
+    fn set_alternate_screen(&mut self, enabled: bool) -> Result<(), WinterError> {
+        if enabled {
+            show_alternate_screen(self);
+        } else {
+            hide_alternate_screen(self);
+        }
+        Ok(())
+    }
+    fn draw(&mut self, diff: Vec<(u16, u16, &Cell)>) -> RenderStats {
+        buffer::draw(self, diff)
+    }
+    /// Move rows already on screen, see `Buffer::find_scroll`.
+    fn scroll(&mut self, scroll: Scroll) {
+        set_scroll_region(self, scroll.top + 1, scroll.bottom);
+        if scroll.amount > 0 {
+            shift_up(self, scroll.amount.unsigned_abs());
+        } else {
+            shift_down(self, scroll.amount.unsigned_abs());
+        }
+        reset_scroll_region(self);
+    }
+    fn show_cursor(&mut self) {
+        show_cursor(self);
+    }
+    fn hide_cursor(&mut self) {
+        hide_cursor(self);
+    }
+    fn move_to(&mut self, x: u16, y: u16) {
+        move_to(self, x, y);
+    }
+    fn clear(&mut self) {
+        clear(self);
+    }
+    /// Clear from the cursor to the end of the screen.
+    fn clear_from_cursor(&mut self) {
+        clear_from_cursor(self);
+    }
+    /// Move down a row, on the last one the screen scrolls up into the scrollback.
+    fn line_feed(&mut self) {
+        self.write_all(b"\n").unwrap();
+    }
+}
+
+/// The platform terminal, win32 console on Windows and termios everywhere else.
+pub struct Console {
+    pub stdout: Stdout,
+    pub stdin: Stdin,
+    pub parser: InputParser,
+    /// Output waiting for `flush`, so a whole frame goes out in one write.
+    frame: Vec<u8>,
+    /// Read keys as VT sequences with `ENABLE_VIRTUAL_TERMINAL_INPUT` and decode them with `parser`.
+    #[cfg(windows)]
+    pub virtual_terminal_input: bool,
+    /// Key, mouse button and surrogate state carried between input records.
+    #[cfg(windows)]
+    records: RecordState,
+    /// Mouse input is a console mode on Windows, so both settings are needed to build it.
+    #[cfg(windows)]
+    raw_mode: bool,
+    #[cfg(windows)]
+    mouse_capture: bool,
+    /// Without VT input a paste is a burst of key records, `poll` groups them.
+    #[cfg(windows)]
+    bracketed_paste: bool,
+    /// The console always sends focus records, they are dropped unless this is set.
+    #[cfg(windows)]
+    focus_reporting: bool,
+}
+
+impl Console {
+    pub fn new() -> Self {
+        Self {
+            stdout: stdout(),
+            stdin: stdin(),
+            parser: InputParser::new(),
+            frame: Vec::new(),
+            #[cfg(windows)]
+            virtual_terminal_input: false,
+            #[cfg(windows)]
+            records: RecordState::default(),
+            #[cfg(windows)]
+            raw_mode: false,
+            #[cfg(windows)]
+            mouse_capture: false,
+            #[cfg(windows)]
+            bracketed_paste: false,
+            #[cfg(windows)]
+            focus_reporting: false,
+        }
+    }
+    /// A console that reads input the way `options` set the terminal up, e.g. for `Events`
+    /// next to a `Winter`. Nothing is written, the modes are only remembered.
+    #[cfg_attr(not(windows), allow(unused_variables))]
+    pub fn with_options(options: WinterOptions) -> Self {
+        #[allow(unused_mut)]
+        let mut console = Self::new();
+        #[cfg(windows)]
+        {
+            console.raw_mode = options.raw_mode;
+            console.mouse_capture = options.mouse_capture;
+            console.bracketed_paste = options.bracketed_paste;
+            console.focus_reporting = options.focus_reporting;
+        }
+        console
+    }
+    #[cfg(windows)]
+    fn input_mode(&self) -> u32 {
+        let mut mode = ENABLE_WINDOW_INPUT | ENABLE_EXTENDED_FLAGS;
+        if !self.raw_mode {
+            mode |= ENABLE_PROCESSED_INPUT | ENABLE_LINE_INPUT | ENABLE_ECHO_INPUT;
+        }
+        //Quick edit is what lets the user select text, it has to be off to receive mouse input.
+        if self.mouse_capture {
+            mode |= ENABLE_MOUSE_INPUT;
+        } else {
+            mode |= ENABLE_QUICK_EDIT_MODE;
+        }
+        if self.virtual_terminal_input {
+            mode |= ENABLE_VIRTUAL_TERMINAL_INPUT;
+        }
+        mode
+    }
+    /// Wait for a reply to a query like `CSI 6n`, returns the parameters of the first
+    /// `CSI params final_byte` sequence. Any other input is passed on to `parser`.
+    ///
+    /// Needs raw mode, otherwise the reply is stuck in the line buffer.
+    #[cfg(unix)]
+    pub fn read_reply(
+        &mut self,
+        final_byte: u8,
+        timeout: Duration,
+    ) -> Result<Option<String>, WinterError> {
+        let mut bytes = Vec::new();
+        let reply = self.read_any_reply(&mut bytes, &[final_byte], timeout);
+        self.parser.feed(&bytes);
+        Ok(reply?.map(|(_, reply)| reply))
+    }
+    /// Same as `read_reply` but stops at the first reply ending in any of `final_bytes`.
+    /// Reading starts with `bytes` and whatever came after the reply is left there,
+    /// so several replies can be read before it goes to `parser`.
+    #[cfg(unix)]
+    fn read_any_reply(
+        &mut self,
+        bytes: &mut Vec<u8>,
+        final_bytes: &[u8],
+        timeout: Duration,
+    ) -> Result<Option<(u8, String)>, WinterError> {
+        let now = Instant::now();
+        let handle = self.stdin.as_raw_handle();
+
+        loop {
+            let mut i = 0;
+            while i + 1 < bytes.len() {
+                if bytes[i] != 0x1b || bytes[i + 1] != b'[' {
+                    i += 1;
+                    continue;
+                }
+                let mut end = i + 2;
+                while end < bytes.len() && (0x20..=0x3f).contains(&bytes[end]) {
+                    end += 1;
+                }
+                if end < bytes.len() && final_bytes.contains(&bytes[end]) {
+                    let reply = String::from_utf8_lossy(&bytes[i + 2..end]).to_string();
+                    let final_byte = bytes[end];
+                    self.parser.feed(&bytes[..i]);
+                    bytes.drain(..=end);
+                    return Ok(Some((final_byte, reply)));
+                }
+                i += 1;
+            }
+
+            let leftover = timeout.saturating_sub(now.elapsed());
+            if leftover.is_zero() || !event_ready(handle, Some(leftover))? {
+                return Ok(None);
+            }
+
+            if resized() {
+                let (width, height) = self.size()?;
+                self.parser.push(Event::Resize(width, height), KeyState(0));
+            }
+            if stopped() {
+                self.parser.push(Event::Suspend, KeyState(0));
+            }
+            if continued() {
+                self.parser.push(Event::Resume, KeyState(0));
+            }
+            if !input_ready(handle)? {
+                continue;
+            }
+
+            let mut buf = [0; 1024];
+            let len = read_input(handle, &mut buf)?;
+            bytes.extend_from_slice(&buf[..len]);
+        }
+    }
+    /// Feed the character of a key press to `parser`.
+    #[cfg(windows)]
+    fn feed_key(&mut self, record: &INPUT_RECORD) {
+        let key_event = unsafe { record.Event.KeyEvent() };
+        if key_event.bKeyDown != 1 {
+            return;
+        }
+
+        let unit = unsafe { *key_event.uChar.UnicodeChar() };
+        if (0xD800..0xDC00).contains(&unit) {
+            self.records.surrogate = Some(unit);
+            return;
+        }
+
+        let units = self.records.surrogate.take().into_iter().chain([unit]);
+        for char in char::decode_utf16(units).filter_map(Result::ok) {
+            let mut bytes = [0; 4];
+            self.parser.feed(char.encode_utf8(&mut bytes).as_bytes());
+        }
+    }
+    /// A burst of key records queued behind a key press is a paste when it's too long to
+    /// have been typed ahead, no key in it is held down and no Ctrl or Alt is involved,
+    /// e.g. a queued Ctrl+C. `None` leaves the records to be read as key presses.
+    #[cfg(windows)]
+    fn read_paste(
+        &mut self,
+        handle: *mut c_void,
+        record: &INPUT_RECORD,
+    ) -> Result<Option<String>, WinterError> {
+        if key_char(record).is_none() {
+            return Ok(None);
+        }
+        //Only looked at until it's known to be a paste.
+        let queued = unsafe { peek_input_events(handle, event_count(handle)?)? };
+        let keys = queued
+            .iter()
+            .take_while(|record| record.EventType == KEY_EVENT)
+            .count();
+        let burst = || std::iter::once(record).chain(&queued[..keys]);
+
+        //Pasted characters are each pressed and released, a held key repeats without a release.
+        let mut down = None;
+        for record in burst() {
+            let key_event = unsafe { record.Event.KeyEvent() };
+            let modifiers =
+                LEFT_CTRL_PRESSED | RIGHT_CTRL_PRESSED | LEFT_ALT_PRESSED | RIGHT_ALT_PRESSED;
+            if key_event.dwControlKeyState & modifiers != 0 || key_event.wRepeatCount > 1 {
+                return Ok(None);
+            }
+            let key = Some(key_event.wVirtualKeyCode);
+            match key_char(record) {
+                Some(_) if down == key => return Ok(None),
+                Some(_) => down = key,
+                None if key_event.bKeyDown == 0 && down == key => down = None,
+                None => {}
+            }
+        }
+
+        let units = burst().filter_map(key_char);
+        let text: String = char::decode_utf16(units).filter_map(Result::ok).collect();
+        let control = |char: char| char.is_control() && !matches!(char, '\r' | '\n' | '\t');
+        if text.chars().count() < PASTE_MIN_CHARS || text.chars().any(control) {
+            return Ok(None);
+        }
+        for _ in 0..keys {
+            read_input_event(handle)?;
+        }
+        self.records.held = None;
+        Ok(Some(input::paste_text(text.as_bytes())))
+    }
+}
+
+/// Fewer characters than this could have been typed while the app was busy,
+/// so they are read as key presses even when they arrive together.
+#[cfg(windows)]
+const PASTE_MIN_CHARS: usize = 8;
+
+/// The UTF-16 unit of a key press, `None` for releases and keys without one.
+#[cfg(windows)]
+fn key_char(record: &INPUT_RECORD) -> Option<u16> {
+    if record.EventType != KEY_EVENT {
+        return None;
+    }
+    let key_event = unsafe { record.Event.KeyEvent() };
+    let unit = unsafe { *key_event.uChar.UnicodeChar() };
+    (key_event.bKeyDown == 1 && unit != 0).then_some(unit)
+}
+
+impl Default for Console {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Write for Console {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.frame.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        //The capacity is kept for the next frame.
+        let result = self.stdout.write_all(&self.frame);
+        self.frame.clear();
+        result?;
+        self.stdout.flush()
+    }
+}
+
+impl Backend for Console {
+    fn size(&mut self) -> Result<(u16, u16), WinterError> {
+        Ok(info(self.stdout.as_raw_handle())?.window_size)
+    }
+    fn buffered_len(&self) -> usize {
+        self.frame.len()
+    }
+    fn init(&mut self) -> Result<(), WinterError> {
+        if !self.stdin.is_terminal() || !self.stdout.is_terminal() {
+            return Err(WinterError::NotATty);
+        }
+
+        #[cfg(windows)]
+        {
+            //Enable ANSI codes on conhost terminals, can also use:
+            //[HKEY_CURRENT_USER\Console]
+            //"VirtualTerminalLevel"=dword:00000001
+            //https://ss64.com/nt/syntax-ansi.html
+            set_mode(
+                self.stdout.as_raw_handle(),
+                ENABLE_PROCESSED_OUTPUT | ENABLE_VIRTUAL_TERMINAL_PROCESSING,
+            )?;
+            set_mode(self.stdin.as_raw_handle(), self.input_mode())?;
+        }
+
+        #[cfg(unix)]
+        watch_signals()?;
+
+        Ok(())
+    }
+    fn uninit(&mut self) -> Result<(), WinterError> {
+        self.flush()?;
+        Ok(())
+    }
+    fn set_raw_mode(&mut self, enabled: bool) -> Result<(), WinterError> {
+        #[cfg(windows)]
+        {
+            self.raw_mode = enabled;
+            set_mode(self.stdin.as_raw_handle(), self.input_mode())
+        }
+
+        #[cfg(unix)]
+        if enabled {
+            enable_raw_mode(self.stdin.as_raw_handle())
+        } else {
+            disable_raw_mode(self.stdin.as_raw_handle())
+        }
+    }
+    fn cursor_position(&mut self) -> Result<(u16, u16), WinterError> {
+        #[cfg(windows)]
+        {
+            let info = info(self.stdout.as_raw_handle())?;
+            let (x, y) = info.cursor_position;
+            let (left, top) = info.window_position;
+            Ok((x.saturating_sub(left), y.saturating_sub(top)))
+        }
+
+        #[cfg(unix)]
+        {
+            write!(self, "\x1b[6n")?;
+            self.flush()?;
+
+            //Cursor position report: `CSI row ; column R`
+            let Some(reply) = self.read_reply(b'R', Duration::from_secs(1))? else {
+                return Ok((0, 0));
+            };
+            let mut params = reply.split(';').map(|p| p.parse::<u16>().unwrap_or(1));
+            let y = params.next().unwrap_or(1);
+            let x = params.next().unwrap_or(1);
+            Ok((x.saturating_sub(1), y.saturating_sub(1)))
+        }
+    }
+    #[cfg(unix)]
+    fn stop_process(&mut self) -> Result<(), WinterError> {
+        self.flush()?;
+        stop_process();
+        Ok(())
+    }
+    #[cfg(unix)]
+    fn set_job_control(&mut self, enabled: bool) -> Result<(), WinterError> {
+        if enabled {
+            catch_job_control();
+        } else {
+            release_job_control();
+        }
+        Ok(())
+    }
+    #[cfg(unix)]
+    fn synchronized_output(&mut self) -> Result<bool, WinterError> {
+        //Primary device attributes come after, every terminal answers those
+        //so there's no waiting on one that ignores the mode request.
+        write!(self, "\x1b[?2026$p\x1b[c")?;
+        self.flush()?;
+
+        let timeout = Duration::from_secs(1);
+        let mut bytes = Vec::new();
+        let supported = match self.read_any_reply(&mut bytes, b"yc", timeout) {
+            //`CSI ? 2026 ; Ps $ y`, 1 is set, 2 is reset and 3 is permanently set.
+            Ok(Some((b'y', reply))) => {
+                let supported = matches!(reply.as_str(), "?2026;1$" | "?2026;2$" | "?2026;3$");
+                self.read_any_reply(&mut bytes, b"c", timeout)
+                    .map(|_| supported)
+            }
+            reply => reply.map(|_| false),
+        };
+        self.parser.feed(&bytes);
+        supported
+    }
+    #[cfg(windows)]
+    fn set_mouse_capture(&mut self, enabled: bool) -> Result<(), WinterError> {
+        self.mouse_capture = enabled;
+        set_mode(self.stdin.as_raw_handle(), self.input_mode())?;
+
+        //VT input reports the mouse as escape sequences instead of records.
+        if self.virtual_terminal_input {
+            if enabled {
+                enable_mouse_capture(self);
+            } else {
+                disable_mouse_capture(self);
+            }
+        }
+        Ok(())
+    }
+    #[cfg(windows)]
+    fn set_bracketed_paste(&mut self, enabled: bool) -> Result<(), WinterError> {
+        self.bracketed_paste = enabled;
+        if self.virtual_terminal_input {
+            if enabled {
+                enable_bracketed_paste(self);
+            } else {
+                disable_bracketed_paste(self);
+            }
+        }
+        Ok(())
+    }
+    #[cfg(windows)]
+    fn set_focus_reporting(&mut self, enabled: bool) -> Result<(), WinterError> {
+        self.focus_reporting = enabled;
+        if self.virtual_terminal_input {
+            if enabled {
+                enable_focus_reporting(self);
+            } else {
+                disable_focus_reporting(self);
+            }
+        }
+        Ok(())
+    }
+    /// The console itself only sends key records, a terminal on the other end of VT input may not.
+    /// After a panic `uninit` has already popped the flags, so they aren't popped again.
+    fn set_kitty_keyboard(&mut self, enabled: bool) -> Result<(), WinterError> {
+        #[cfg(windows)]
+        if !self.virtual_terminal_input {
+            return Ok(());
+        }
+        if enabled {
+            push_keyboard_flags(self, KEYBOARD_FLAGS);
+            KEYBOARD_FLAGS_PUSHED.store(true, Ordering::Release);
+        } else if KEYBOARD_FLAGS_PUSHED.swap(false, Ordering::AcqRel) {
+            pop_keyboard_flags(self);
+        }
+        Ok(())
+    }
+    fn poll(&mut self, timeout: Duration) -> Result<Option<(Event, KeyState)>, WinterError> {
+        let now = Instant::now();
+        let handle = self.stdin.as_raw_handle();
+
+        loop {
+            if let Some(event) = self.parser.next() {
+                return Ok(Some(event));
+            }
+
+            //Don't sleep past the end of a pending escape sequence.
+            let leftover = timeout.saturating_sub(now.elapsed());
+            let wait = match self.parser.timeout(Instant::now()) {
+                Some(escape) => escape.min(leftover),
+                None => leftover,
+            };
+
+            #[cfg(windows)]
+            if event_ready(handle, Some(wait))? && event_count(handle)? != 0 {
+                let input_event = read_input_event(handle)?;
+                if self.virtual_terminal_input && input_event.EventType == KEY_EVENT {
+                    self.feed_key(&input_event);
+                    continue;
+                }
+                //VT input reports focus as `CSI I` and `CSI O` as well.
+                if input_event.EventType == FOCUS_EVENT
+                    && (!self.focus_reporting || self.virtual_terminal_input)
+                {
+                    continue;
+                }
+                if self.bracketed_paste {
+                    if let Some(text) = self.read_paste(handle, &input_event)? {
+                        return Ok(Some((Event::Paste(text), KeyState(0))));
+                    }
+                }
+
+                let event = unsafe { convert_event(input_event, &mut self.records) };
+                return Ok(event.map(|event| {
+                    let state = match &event {
+                        Event::Key(key) => key.modifiers,
+                        Event::Mouse(mouse) => mouse.modifiers,
+                        _ => KeyState(0),
+                    };
+                    (event, state)
+                }));
+            }
+
+            #[cfg(unix)]
+            if event_ready(handle, Some(wait))? {
+                //The pipe wakes us once for every signal that arrived, queue all of them.
+                if resized() {
+                    let (width, height) = self.size()?;
+                    self.parser.push(Event::Resize(width, height), KeyState(0));
+                }
+                if stopped() {
+                    self.parser.push(Event::Suspend, KeyState(0));
+                }
+                if continued() {
+                    self.parser.push(Event::Resume, KeyState(0));
+                }
+                if input_ready(handle)? {
+                    let mut buf = [0; 1024];
+                    let len = read_input(handle, &mut buf)?;
+                    self.parser.feed(&buf[..len]);
+                }
+                continue;
+            }
+
+            //Timeout elapsed
+            if now.elapsed().as_millis() >= timeout.as_millis() {
+                return Ok(self.parser.next());
+            }
+        }
+    }
+}
+
+/// Renders into any `Write`, e.g. a `Vec<u8>`, a file or another tty.
+/// The size is whatever the caller says it is and there is no input.
+/// Output is held until `flush`, which writes it all at once.
+///
+/// ```
+/// use winter::*;
+///
+/// let mut winter = Winter::with_backend(WriterBackend::new(Vec::new(), 20, 5))?;
+/// let buf = winter.buffer();
+/// lines!("hello").draw(buf.area, buf);
+/// winter.draw()?;
+///
+/// let output = String::from_utf8_lossy(&winter.backend.writer);
+/// assert!(output.contains("hello"));
+/// # Ok::<(), WinterError>(())
+/// ```
+#[derive(Debug)]
+pub struct WriterBackend<W: Write> {
+    pub writer: W,
+    pub size: (u16, u16),
+    /// Events returned by `poll`, front first.
+    pub events: VecDeque<(Event, KeyState)>,
+    /// Reported by `cursor_position`, zero-based.
+    pub cursor: (u16, u16),
+    frame: Vec<u8>,
+}
+
+impl<W: Write> WriterBackend<W> {
+    pub fn new(writer: W, width: u16, height: u16) -> Self {
+        Self {
+            writer,
+            size: (width, height),
+            events: VecDeque::new(),
+            cursor: (0, 0),
+            frame: Vec::new(),
+        }
+    }
+    pub fn push_event(&mut self, event: Event, state: KeyState) {
+        self.events.push_back((event, state));
+    }
+    /// Change the size and queue the matching `Event::Resize`.
+    pub fn resize(&mut self, width: u16, height: u16) {
+        self.size = (width, height);
+        self.push_event(Event::Resize(width, height), KeyState::default());
+    }
+}
+
+impl<W: Write> Write for WriterBackend<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.frame.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        let result = self.writer.write_all(&self.frame);
+        self.frame.clear();
+        result?;
+        self.writer.flush()
+    }
+}
+
+impl<W: Write> Backend for WriterBackend<W> {
+    fn size(&mut self) -> Result<(u16, u16), WinterError> {
+        Ok(self.size)
+    }
+    fn buffered_len(&self) -> usize {
+        self.frame.len()
+    }
+    fn init(&mut self) -> Result<(), WinterError> {
+        Ok(())
+    }
+    fn uninit(&mut self) -> Result<(), WinterError> {
+        self.flush()?;
+        Ok(())
+    }
+    /// There is nothing to read from, so this sleeps for `timeout` unless an event was queued.
+    fn poll(&mut self, timeout: Duration) -> Result<Option<(Event, KeyState)>, WinterError> {
+        if let Some(event) = self.events.pop_front() {
+            return Ok(Some(event));
+        }
+        std::thread::sleep(timeout);
+        Ok(None)
+    }
+    /// Raw mode belongs to the tty, not the writer.
+    fn set_raw_mode(&mut self, _: bool) -> Result<(), WinterError> {
+        Ok(())
+    }
+    fn cursor_position(&mut self) -> Result<(u16, u16), WinterError> {
+        Ok(self.cursor)
+    }
+}
+
+/// A headless backend with a virtual screen and a scripted event queue.
+///
+/// ```
+/// use winter::*;
+///
+/// let mut backend = TestBackend::new(20, 5);
+/// let q: Event = KeyEvent::new(KeyCode::Char('q'), KeyState::default()).into();
+/// backend.push_event(q.clone(), KeyState::default());
+///
+/// let mut winter = Winter::with_backend(backend)?;
+/// let buf = winter.buffer();
+/// lines!("hello").draw(buf.area, buf);
+/// winter.draw()?;
+///
+/// assert_eq!(winter.poll()?, Some((q, KeyState::default())));
+/// assert_eq!(winter.backend.lines()[0].trim_end(), "hello");
+/// # Ok::<(), WinterError>(())
+/// ```
+#[derive(Debug)]
+pub struct TestBackend {
+    /// What the terminal would currently display.
+    pub screen: Buffer,
+    /// Events returned by `poll`, front first.
+    pub events: VecDeque<(Event, KeyState)>,
+    /// Every byte written that wasn't a diff, e.g. `clear` or `show_cursor`.
+    pub output: Vec<u8>,
+    /// Zero-based cursor position.
+    pub cursor: (u16, u16),
+    pub cursor_visible: bool,
+    pub initialized: bool,
+    pub raw_mode: bool,
+    pub mouse_capture: bool,
+    pub bracketed_paste: bool,
+    pub focus_reporting: bool,
+    pub kitty_keyboard: bool,
+    pub alternate_screen: bool,
+    /// Job control signals are caught, see `Backend::set_job_control`.
+    pub job_control: bool,
+}
+
+impl TestBackend {
+    pub fn new(width: u16, height: u16) -> Self {
+        Self {
+            screen: Buffer::empty(Rect::new(0, 0, width, height)),
+            events: VecDeque::new(),
+            output: Vec::new(),
+            cursor: (0, 0),
+            cursor_visible: true,
+            initialized: false,
+            raw_mode: false,
+            mouse_capture: false,
+            bracketed_paste: false,
+            focus_reporting: false,
+            kitty_keyboard: false,
+            alternate_screen: false,
+            job_control: false,
+        }
+    }
+    pub fn push_event(&mut self, event: Event, state: KeyState) {
+        self.events.push_back((event, state));
+    }
+    /// Resize the virtual screen and queue the matching `Event::Resize`.
+    pub fn resize(&mut self, width: u16, height: u16) {
+        self.screen.resize(Rect::new(0, 0, width, height));
+        self.screen.reset();
+        self.push_event(Event::Resize(width, height), KeyState::default());
+    }
+    /// The symbols on screen, one string per row.
+    pub fn lines(&self) -> Vec<String> {
+        self.screen.to_lines()
+    }
+}
+
+impl Write for TestBackend {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.output.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Backend for TestBackend {
+    fn size(&mut self) -> Result<(u16, u16), WinterError> {
+        Ok((self.screen.area.width, self.screen.area.height))
+    }
+    fn init(&mut self) -> Result<(), WinterError> {
+        self.initialized = true;
+        Ok(())
+    }
+    fn uninit(&mut self) -> Result<(), WinterError> {
+        self.initialized = false;
+        Ok(())
+    }
+    fn set_raw_mode(&mut self, enabled: bool) -> Result<(), WinterError> {
+        self.raw_mode = enabled;
+        Ok(())
+    }
+    fn cursor_position(&mut self) -> Result<(u16, u16), WinterError> {
+        Ok(self.cursor)
+    }
+    fn set_mouse_capture(&mut self, enabled: bool) -> Result<(), WinterError> {
+        self.mouse_capture = enabled;
+        Ok(())
+    }
+    fn set_bracketed_paste(&mut self, enabled: bool) -> Result<(), WinterError> {
+        self.bracketed_paste = enabled;
+        Ok(())
+    }
+    fn set_focus_reporting(&mut self, enabled: bool) -> Result<(), WinterError> {
+        self.focus_reporting = enabled;
+        Ok(())
+    }
+    fn set_kitty_keyboard(&mut self, enabled: bool) -> Result<(), WinterError> {
+        self.kitty_keyboard = enabled;
+        Ok(())
+    }
+    fn set_alternate_screen(&mut self, enabled: bool) -> Result<(), WinterError> {
+        self.alternate_screen = enabled;
+        Ok(())
+    }
+    fn set_job_control(&mut self, enabled: bool) -> Result<(), WinterError> {
+        self.job_control = enabled;
+        Ok(())
+    }
+    fn poll(&mut self, _: Duration) -> Result<Option<(Event, KeyState)>, WinterError> {
+        Ok(self.events.pop_front())
+    }
+    fn draw(&mut self, diff: Vec<(u16, u16, &Cell)>) -> RenderStats {
+        let cells = diff.len();
+        for (x, y, cell) in diff {
+            if let Ok(c) = self.screen.get_mut(x, y) {
+                c.clone_from(cell);
+            }
+            self.cursor = (x + 1, y);
+        }
+        RenderStats {
+            cells,
+            ..Default::default()
+        }
+    }
+    fn scroll(&mut self, scroll: Scroll) {
+        self.screen.scroll(scroll);
+    }
+    fn show_cursor(&mut self) {
+        self.cursor_visible = true;
+    }
+    fn hide_cursor(&mut self) {
+        self.cursor_visible = false;
+    }
+    fn move_to(&mut self, x: u16, y: u16) {
+        //Escape sequences are one-based.
+        self.cursor = (x.saturating_sub(1), y.saturating_sub(1));
+    }
+    fn clear(&mut self) {
+        self.screen.reset();
+    }
+    fn clear_from_cursor(&mut self) {
+        let (x, y) = self.cursor;
+        let width = self.screen.area.width;
+        for row in y..self.screen.area.height {
+            let start = if row == y { x } else { 0 };
+            for col in start..width {
+                if let Ok(cell) = self.screen.get_mut(col, row) {
+                    cell.reset();
+                }
+            }
+        }
+    }
+    fn line_feed(&mut self) {
+        let height = self.screen.area.height;
+        if self.cursor.1 + 1 >= height {
+            self.screen.scroll(Scroll {
+                top: 0,
+                bottom: height,
+                amount: 1,
+            });
+        } else {
+            self.cursor.1 += 1;
+        }
+    }
+}
@@ -0,0 +1,47 @@
+//! Standard base64 with padding, just enough for OSC 52.
+
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// ```
+/// assert_eq!(winter::base64::encode(b"winter"), "d2ludGVy");
+/// assert_eq!(winter::base64::encode(b"snow"), "c25vdw==");
+/// ```
+pub fn encode(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = (chunk[0] as u32) << 16
+            | (*chunk.get(1).unwrap_or(&0) as u32) << 8
+            | *chunk.get(2).unwrap_or(&0) as u32;
+        //A chunk of n bytes is n + 1 characters, the rest is padding.
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(n >> (18 - 6 * i)) as usize & 0x3f] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+/// `None` if `encoded` has characters outside the alphabet.
+///
+/// ```
+/// assert_eq!(winter::base64::decode("c25vdw=="), Some(b"snow".to_vec()));
+/// assert_eq!(winter::base64::decode("c25v!"), None);
+/// ```
+pub fn decode(encoded: &str) -> Option<Vec<u8>> {
+    let mut decoded = Vec::with_capacity(encoded.len() / 4 * 3);
+    let mut n = 0u32;
+    let mut bits = 0;
+    for byte in encoded.bytes().filter(|&b| b != b'=') {
+        let value = ALPHABET.iter().position(|&c| c == byte)? as u32;
+        n = n << 6 | value;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            decoded.push((n >> bits) as u8);
+        }
+    }
+    Some(decoded)
+}
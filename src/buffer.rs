@@ -1,5 +1,14 @@
 use crate::{layout::Rect, *};
-use std::{cmp::min, io::Write};
+use std::{
+    cmp::min,
+    collections::HashMap,
+    fmt::Write as _,
+    hash::{Hash, Hasher},
+    io::Write,
+    path::Path,
+    sync::Arc,
+    time::Duration,
+};
 use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
 pub fn draw_modifier<W: Write>(w: &mut W, from: Modifier, to: Modifier) {
@@ -56,18 +65,86 @@ pub fn draw_modifier<W: Write>(w: &mut W, from: Modifier, to: Modifier) {
     }
 }
 
-///Note: Appends the cells to a buffer. Hides the cursor.
-pub fn draw<W: Write>(w: &mut W, diff: Vec<(u16, u16, &Cell)>) {
+///Setting and resetting a scroll region is about this many bytes, so scrolling has to save more cells.
+pub const MIN_SCROLL_SAVED: usize = 32;
+///Most shifts `Buffer::find_scroll` tries, each one checks every region.
+const MAX_SCROLL_CANDIDATES: usize = 4;
+
+/// Rows `top..bottom` shifted by `amount` between two frames, up if positive and down if negative.
+/// Rows are absolute like the positions from `Buffer::diff`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Scroll {
+    pub top: u16,
+    pub bottom: u16,
+    pub amount: i16,
+}
+
+/// What drawing a frame cost, see `Winter::stats`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RenderStats {
+    /// Cells that were different from the last frame.
+    pub cells: usize,
+    /// Bytes sent for the whole frame, including cursor moves, scroll regions and
+    /// synchronized update markers. Only counted by backends that hold output until `flush`.
+    pub bytes: usize,
+    pub cursor_moves: usize,
+    /// Rows moved with a scroll region instead of being drawn again.
+    pub scroll: Option<Scroll>,
+    /// Time spent in `Buffer::find_scroll` and `Buffer::diff`.
+    pub diff_time: Duration,
+    /// Time spent turning the diff into escape sequences and writing them out.
+    pub write_time: Duration,
+}
+
+///FNV-1a, rows are hashed every frame and SipHash is too slow for that.
+struct Fnv(u64);
+
+impl Hasher for Fnv {
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = (self.0 ^ byte as u64).wrapping_mul(0x100000001b3);
+        }
+    }
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+///Counts the bytes written to `inner`.
+struct Counter<'a, W> {
+    inner: &'a mut W,
+    bytes: usize,
+}
+
+impl<W: Write> Write for Counter<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let len = self.inner.write(buf)?;
+        self.bytes += len;
+        Ok(len)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+///Note: Appends the cells to a buffer.
+pub fn draw<W: Write>(w: &mut W, diff: Vec<(u16, u16, &Cell)>) -> RenderStats {
+    let w = &mut Counter { inner: w, bytes: 0 };
+    let mut stats = RenderStats {
+        cells: diff.len(),
+        ..Default::default()
+    };
     let mut fg = Color::Reset;
     let mut bg = Color::Reset;
     //TODO: Maybe have a variable for all modifiers?
     //That way we can turn each indiviually on and off.
     let mut modifier = Modifier::empty();
+    let mut link: Option<&Arc<str>> = None;
     let mut last_pos: Option<(u16, u16)> = None;
 
     //Move to start.
-    hide_cursor(w);
     move_to(w, 1, 1);
+    stats.cursor_moves += 1;
 
     for (x, y, cell) in diff {
         //Apparently 0, 0 and 1, 1 are the same?
@@ -75,6 +152,7 @@ pub fn draw<W: Write>(w: &mut W, diff: Vec<(u16, u16, &Cell)>) {
         let y = y + 1;
         if !matches!(last_pos, Some(p) if x == p.0 + 1 && y == p.1) {
             move_to(w, x, y);
+            stats.cursor_moves += 1;
         }
         last_pos = Some((x, y));
 
@@ -90,16 +168,30 @@ pub fn draw<W: Write>(w: &mut W, diff: Vec<(u16, u16, &Cell)>) {
             write!(w, "{}", cell.bg.bg_code()).unwrap();
             bg = cell.bg;
         }
+        if cell.link.as_ref() != link {
+            if link.is_some() {
+                end_hyperlink(w);
+            }
+            if let Some(target) = &cell.link {
+                start_hyperlink(w, target);
+            }
+            link = cell.link.as_ref();
+        }
 
         write!(w, "{}", cell.symbol).unwrap();
     }
+    if link.is_some() {
+        end_hyperlink(w);
+    }
 
     //Always write reset as the last symbol.
     //That way styles never stay when the program is closed.
     write!(w, "{}", RESET).unwrap();
+    stats.bytes = w.bytes;
+    stats
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Buffer {
     /// The area represented by this buffer
     pub area: Rect,
@@ -117,7 +209,150 @@ impl Buffer {
         }
         Self { area, content }
     }
-    pub fn get_mut(&mut self, x: u16, y: u16) -> Result<&mut Cell, String> {
+    /// One row per line, as wide as the widest line. Useful for the expected side of `assert_buffer_eq!`.
+    ///
+    /// ```
+    /// use winter::*;
+    ///
+    /// let mut buf = Buffer::empty(Rect::new(0, 0, 5, 3));
+    /// block().draw(buf.area, &mut buf);
+    /// assert_buffer_eq!(buf, Buffer::with_lines(["╭───╮", "│   │", "╰───╯"]));
+    /// ```
+    pub fn with_lines<S: AsRef<str>>(lines: impl IntoIterator<Item = S>) -> Self {
+        let lines: Vec<S> = lines.into_iter().collect();
+        let width = lines
+            .iter()
+            .map(|line| line.as_ref().width())
+            .max()
+            .unwrap_or(0) as u16;
+        let mut buffer = Buffer::empty(Rect::new(0, 0, width, lines.len() as u16));
+        if width != 0 {
+            for (y, line) in lines.iter().enumerate() {
+                buffer.set_stringn(0, y as u16, line, width as usize, Style::default());
+            }
+        }
+        buffer
+    }
+    /// The symbols, one string per row. Cells hidden behind a multi-width character are skipped.
+    pub fn to_lines(&self) -> Vec<String> {
+        let width = self.area.width.max(1) as usize;
+        self.content
+            .chunks(width)
+            .map(|row| {
+                let mut line = String::new();
+                let mut skip = 0;
+                for cell in row {
+                    if skip > 0 {
+                        skip -= 1;
+                        continue;
+                    }
+                    line.push_str(&cell.symbol);
+                    skip = cell.symbol.width().saturating_sub(1);
+                }
+                line
+            })
+            .collect()
+    }
+    /// Text dump with the styles listed below the symbols, used for golden files.
+    ///
+    /// Each style line is a run of cells in one row, default colors and modifiers are left out:
+    /// `y=1 x=1..4 fg=Black bg=White modifier=BOLD|ITALIC`
+    pub fn snapshot(&self) -> String {
+        let mut snapshot = String::new();
+        for line in self.to_lines() {
+            snapshot.push_str(&line);
+            snapshot.push('\n');
+        }
+        snapshot.push_str("---\n");
+
+        let width = self.area.width.max(1) as usize;
+        for (y, row) in self.content.chunks(width).enumerate() {
+            let mut x = 0;
+            while x < row.len() {
+                let cell = &row[x];
+                let end = x + row[x..]
+                    .iter()
+                    .take_while(|c| {
+                        (c.fg, c.bg, c.modifier, &c.link)
+                            == (cell.fg, cell.bg, cell.modifier, &cell.link)
+                    })
+                    .count();
+                if cell.fg != Color::Reset
+                    || cell.bg != Color::Reset
+                    || !cell.modifier.is_empty()
+                    || cell.link.is_some()
+                {
+                    write!(snapshot, "y={} x={}..{}", y, x, end).unwrap();
+                    if cell.fg != Color::Reset {
+                        write!(snapshot, " fg={:?}", cell.fg).unwrap();
+                    }
+                    if cell.bg != Color::Reset {
+                        write!(snapshot, " bg={:?}", cell.bg).unwrap();
+                    }
+                    if !cell.modifier.is_empty() {
+                        let mut modifier = String::new();
+                        bitflags::parser::to_writer(&cell.modifier, &mut modifier).unwrap();
+                        write!(snapshot, " modifier={}", modifier.replace(" | ", "|")).unwrap();
+                    }
+                    if let Some(link) = &cell.link {
+                        write!(snapshot, " link={}", link).unwrap();
+                    }
+                    snapshot.push('\n');
+                }
+                x = end;
+            }
+        }
+        snapshot
+    }
+    /// Read back the output of `snapshot`. The area starts at 0, 0.
+    pub fn from_snapshot(snapshot: &str) -> Result<Self, WinterError> {
+        let invalid = WinterError::InvalidSnapshot;
+        let snapshot = snapshot.replace("\r\n", "\n");
+        let (lines, styles) = snapshot
+            .rsplit_once("---\n")
+            .ok_or_else(|| invalid("missing the `---` separator".into()))?;
+        let mut buffer = Buffer::with_lines(lines.lines());
+
+        for line in styles.lines() {
+            let mut y = None;
+            let mut range = None;
+            let mut style = Style::default();
+            let mut link: Option<Arc<str>> = None;
+            for field in line.split_whitespace() {
+                let (key, value) = field
+                    .split_once('=')
+                    .ok_or_else(|| invalid(format!("style field {field}")))?;
+                match key {
+                    "y" => y = value.parse::<u16>().ok(),
+                    "x" => {
+                        range = value
+                            .split_once("..")
+                            .and_then(|(start, end)| Some((start.parse().ok()?, end.parse().ok()?)))
+                    }
+                    "fg" => style.fg = value.parse()?,
+                    "bg" => style.bg = value.parse()?,
+                    "modifier" => {
+                        style.modifier = bitflags::parser::from_str(value)
+                            .map_err(|err| invalid(format!("modifier {value}: {err}")))?
+                    }
+                    "link" => link = Some(value.into()),
+                    _ => return Err(invalid(format!("unknown style field {key}"))),
+                }
+            }
+            let (Some(y), Some((start, end))) = (y, range) else {
+                return Err(invalid(format!("style line needs y and x: {line}")));
+            };
+            for x in start..end {
+                let cell = buffer.get_mut(x, y)?;
+                cell.fg = style.fg;
+                cell.bg = style.bg;
+                cell.modifier = style.modifier;
+                cell.link = link.clone();
+            }
+        }
+        Ok(buffer)
+    }
+    pub fn get_mut(&mut self, x: u16, y: u16) -> Result<&mut Cell, WinterError> {
         let i = self.index_of(x, y)?;
         Ok(&mut self.content[i])
     }
@@ -140,6 +375,7 @@ impl Buffer {
             }
 
             let style = lines.style.unwrap_or(line.style);
+            let link: Option<Arc<str>> = line.link.as_deref().map(Into::into);
             let line = if scroll {
                 // If there is overflow, skip characters from the start of the line
                 let mut skip = 0;
@@ -160,6 +396,11 @@ impl Buffer {
             };
 
             let pos = self.set_stringn(x, y, line, remaining_width as usize, style);
+            if link.is_some() {
+                for x in x..pos.0 {
+                    self.get_mut(x, y).unwrap().set_link(link.clone());
+                }
+            }
             let w = pos.0.saturating_sub(x);
             x = pos.0;
             remaining_width = remaining_width.saturating_sub(w);
@@ -199,6 +440,7 @@ impl Buffer {
 
             self.content[index].set_char(s);
             self.content[index].set_style(style);
+            self.content[index].link = None;
             // Reset following cells if multi-width (they would be hidden by the grapheme),
             for i in index + 1..index + width {
                 self.content[i].reset();
@@ -215,20 +457,142 @@ impl Buffer {
             }
         }
     }
-    pub fn index_of(&self, x: u16, y: u16) -> Result<usize, String> {
+    pub fn index_of(&self, x: u16, y: u16) -> Result<usize, WinterError> {
         if !(x >= self.area.left()
             && x < self.area.right()
             && y >= self.area.top()
             && y < self.area.bottom())
         {
-            Err(format!(
-                "Trying to access position outside the buffer: x={}, y={}, area={:?}",
-                x, y, self.area
-            ))
+            Err(WinterError::OutOfBounds {
+                x,
+                y,
+                area: self.area,
+            })
         } else {
             Ok(((y - self.area.y) * self.area.width + (x - self.area.x)) as usize)
         }
     }
+    /// Find the block of rows in `self` that moved the furthest towards `other`,
+    /// e.g. after a `List` scrolled by one item.
+    ///
+    /// Returns `None` when scrolling wouldn't save redrawing at least `MIN_SCROLL_SAVED` cells.
+    ///
+    /// ```
+    /// use winter::{buffer::Scroll, *};
+    ///
+    /// let rows = |range: std::ops::Range<u16>| range.map(|i| format!("Item number {i}"));
+    /// let previous = Buffer::with_lines(rows(1..5));
+    /// let next = Buffer::with_lines(rows(2..6));
+    /// let scroll = previous.find_scroll(&next);
+    /// assert_eq!(scroll, Some(Scroll { top: 0, bottom: 4, amount: 1 }));
+    /// ```
+    pub fn find_scroll(&self, other: &Buffer) -> Option<Scroll> {
+        let height = self.area.height as usize;
+        let width = self.area.width as usize;
+        if self.area != other.area || height < 2 || width == 0 {
+            return None;
+        }
+
+        //Compare rows by hash, checking every shift cell by cell would be too slow.
+        //A collision only makes the estimate wrong, `diff` still compares the cells.
+        let hash_rows = |buffer: &Buffer| -> Vec<u64> {
+            buffer
+                .content
+                .chunks(width)
+                .map(|row| {
+                    let mut hasher = Fnv(0xcbf29ce484222325);
+                    row.hash(&mut hasher);
+                    hasher.finish()
+                })
+                .collect()
+        };
+        let old = hash_rows(self);
+        let new = hash_rows(other);
+        let blank = hash_rows(&Buffer::empty(Rect::new(0, 0, self.area.width, 1)))[0];
+        let changed = |y: usize| (new[y] != old[y]) as isize;
+
+        //Rows saved by leaving the row blank, a prefix sum so any range is one subtraction.
+        let mut blanked = vec![0; height + 1];
+        for y in 0..height {
+            blanked[y + 1] = blanked[y] + changed(y) - (new[y] != blank) as isize;
+        }
+
+        //Only try shifts that some changed row would move into place, most likely first.
+        let mut rows: HashMap<u64, Vec<usize>> = HashMap::new();
+        for (x, &hash) in old.iter().enumerate() {
+            if hash != blank {
+                rows.entry(hash).or_default().push(x);
+            }
+        }
+        let mut votes: HashMap<isize, usize> = HashMap::new();
+        for y in (0..height).filter(|&y| changed(y) == 1) {
+            for &x in rows.get(&new[y]).into_iter().flatten() {
+                *votes.entry(x as isize - y as isize).or_default() += 1;
+            }
+        }
+        let mut shifts: Vec<(isize, usize)> = votes.into_iter().collect();
+        shifts.sort_by_key(|&(shift, votes)| (usize::MAX - votes, shift.unsigned_abs()));
+        shifts.truncate(MAX_SCROLL_CANDIDATES);
+
+        //(rows saved, scroll)
+        let mut best: Option<(isize, Scroll)> = None;
+        for (shift, _) in shifts {
+            let amount = shift.unsigned_abs();
+            //Rows saved by moving row `y + shift` to `y`.
+            let mut moved = vec![0; height + 1];
+            for y in 0..height {
+                let from = y as isize + shift;
+                moved[y + 1] = moved[y];
+                if from >= 0 && (from as usize) < height {
+                    moved[y + 1] += changed(y) - (new[y] != old[from as usize]) as isize;
+                }
+            }
+
+            //Scrolling `top..bottom` moves all but `amount` rows, the rest are uncovered and blank.
+            for top in 0..height - amount {
+                for bottom in top + amount + 1..=height {
+                    let saved = if shift > 0 {
+                        moved[bottom - amount] - moved[top] + blanked[bottom]
+                            - blanked[bottom - amount]
+                    } else {
+                        moved[bottom] - moved[top + amount] + blanked[top + amount] - blanked[top]
+                    };
+                    if saved > 0
+                        && saved as usize * width >= MIN_SCROLL_SAVED
+                        && best.is_none_or(|(best, _)| saved > best)
+                    {
+                        let scroll = Scroll {
+                            top: self.area.y + top as u16,
+                            bottom: self.area.y + bottom as u16,
+                            amount: shift as i16,
+                        };
+                        best = Some((saved, scroll));
+                    }
+                }
+            }
+        }
+        best.map(|(_, scroll)| scroll)
+    }
+    /// Move the rows like the terminal would, uncovered rows are blank.
+    pub fn scroll(&mut self, scroll: Scroll) {
+        let width = self.area.width as usize;
+        let top = scroll.top.saturating_sub(self.area.y) as usize * width;
+        let bottom =
+            (scroll.bottom.saturating_sub(self.area.y) as usize * width).min(self.content.len());
+        if top >= bottom {
+            return;
+        }
+        let region = &mut self.content[top..bottom];
+        let len = region.len();
+        let shift = (scroll.amount.unsigned_abs() as usize * width).min(len);
+        if scroll.amount > 0 {
+            region.rotate_left(shift);
+            region[len - shift..].fill(Cell::default());
+        } else {
+            region.rotate_right(shift);
+            region[..shift].fill(Cell::default());
+        }
+    }
     ///Does not allow for multi-width characters.
     pub fn diff<'a>(&self, other: &'a Buffer) -> Vec<(u16, u16, &'a Cell)> {
         let previous_buffer = &self.content;
@@ -243,8 +607,8 @@ impl Buffer {
         let mut to_skip: usize = 0;
         for (i, (current, previous)) in next_buffer.iter().zip(previous_buffer.iter()).enumerate() {
             if (current != previous || invalidated > 0) && to_skip == 0 {
-                let x = i as u16 % width;
-                let y = i as u16 / width;
+                let x = self.area.x + i as u16 % width;
+                let y = self.area.y + i as u16 / width;
                 updates.push((x, y, &next_buffer[i]));
             }
 
@@ -289,12 +653,14 @@ impl Buffer {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Cell {
     pub symbol: String,
     pub fg: Color,
     pub bg: Color,
     pub modifier: Modifier,
+    /// Hyperlink target, shared by every cell of the same `Text`.
+    pub link: Option<Arc<str>>,
 }
 
 impl Cell {
@@ -328,12 +694,17 @@ impl Cell {
         self.modifier = modifier;
         self
     }
+    pub fn set_link(&mut self, link: Option<Arc<str>>) -> &mut Cell {
+        self.link = link;
+        self
+    }
     pub fn reset(&mut self) {
         self.symbol.clear();
         self.symbol.push(' ');
         self.fg = Color::Reset;
         self.bg = Color::Reset;
         self.modifier = Modifier::empty();
+        self.link = None;
     }
 }
 
@@ -344,6 +715,186 @@ impl Default for Cell {
             fg: Color::Reset,
             bg: Color::Reset,
             modifier: Modifier::empty(),
+            link: None,
         }
     }
 }
+
+/// Describes how `actual` differs from `expected`, `None` if they are the same.
+///
+/// Both grids are printed side by side with a marker for every cell that differs:
+/// `S` symbol, `F` fg, `B` bg, `M` modifier, `L` link and `*` for more than one.
+pub fn compare(expected: &Buffer, actual: &Buffer) -> Option<String> {
+    if expected.area == actual.area && expected.content == actual.content {
+        return None;
+    }
+
+    let mut report = String::new();
+    if expected.area != actual.area {
+        writeln!(
+            report,
+            "area: expected {:?}, actual {:?}",
+            expected.area, actual.area
+        )
+        .unwrap();
+    }
+
+    let same_size =
+        expected.area.width == actual.area.width && expected.area.height == actual.area.height;
+    let expected_lines = expected.to_lines();
+    let actual_lines = actual.to_lines();
+    let expected_width = expected.area.width as usize;
+    let actual_width = actual.area.width as usize;
+    let mut differences = Vec::new();
+
+    writeln!(
+        report,
+        "{:<ew$}   {:<aw$}   S=symbol F=fg B=bg M=modifier L=link *=several",
+        "expected",
+        "actual",
+        ew = expected_width + 2,
+        aw = actual_width + 2,
+    )
+    .unwrap();
+
+    for y in 0..expected_lines.len().max(actual_lines.len()) {
+        let mut marks = String::new();
+        if same_size {
+            for x in 0..expected_width {
+                let i = y * expected_width + x;
+                let (e, a) = (&expected.content[i], &actual.content[i]);
+                let mut kinds = Vec::new();
+                if e.symbol != a.symbol {
+                    kinds.push(('S', format!("symbol {:?} != {:?}", e.symbol, a.symbol)));
+                }
+                if e.fg != a.fg {
+                    kinds.push(('F', format!("fg {:?} != {:?}", e.fg, a.fg)));
+                }
+                if e.bg != a.bg {
+                    kinds.push(('B', format!("bg {:?} != {:?}", e.bg, a.bg)));
+                }
+                if e.modifier != a.modifier {
+                    kinds.push((
+                        'M',
+                        format!("modifier {:?} != {:?}", e.modifier, a.modifier),
+                    ));
+                }
+                if e.link != a.link {
+                    kinds.push(('L', format!("link {:?} != {:?}", e.link, a.link)));
+                }
+                marks.push(match kinds.as_slice() {
+                    [] => ' ',
+                    [(mark, _)] => *mark,
+                    _ => '*',
+                });
+                for (_, difference) in kinds {
+                    let position = (actual.area.x + x as u16, actual.area.y + y as u16);
+                    differences.push(format!("{:?} {}", position, difference));
+                }
+            }
+        }
+
+        let pad = |lines: &[String], width: usize| {
+            let line = lines.get(y).map(String::as_str).unwrap_or("");
+            format!(
+                "\"{}\"{}",
+                line,
+                " ".repeat(width.saturating_sub(line.width()))
+            )
+        };
+        let row = format!(
+            "{}   {}   {}",
+            pad(&expected_lines, expected_width),
+            pad(&actual_lines, actual_width),
+            marks
+        );
+        writeln!(report, "{}", row.trim_end()).unwrap();
+    }
+
+    for difference in differences {
+        writeln!(report, "{}", difference).unwrap();
+    }
+    Some(report)
+}
+
+/// Panics with a side by side diff if the buffers are different.
+///
+/// ```should_panic
+/// use winter::*;
+///
+/// let mut buf = Buffer::empty(Rect::new(0, 0, 5, 1));
+/// buf.set_stringn(0, 0, "hello", 5, fg(Red));
+/// assert_buffer_eq!(buf, Buffer::with_lines(["hello"]));
+/// ```
+#[macro_export]
+macro_rules! assert_buffer_eq {
+    ($actual:expr, $expected:expr) => {
+        if let Some(report) = $crate::buffer::compare(&$expected, &$actual) {
+            panic!("Buffers are not equal.\n{}", report);
+        }
+    };
+}
+
+/// Set this to rewrite golden files instead of comparing against them.
+pub const UPDATE_SNAPSHOTS: &str = "WINTER_UPDATE_SNAPSHOTS";
+
+/// Compare `buffer` against the golden file at `path`. A missing file fails too, so a deleted
+/// snapshot doesn't pass silently, files are only written with `UPDATE_SNAPSHOTS` set.
+/// Use `assert_snapshot!` to put it under `tests/snapshots`.
+#[track_caller]
+pub fn assert_snapshot(path: impl AsRef<Path>, buffer: &Buffer) {
+    let path = path.as_ref();
+    let snapshot = buffer.snapshot();
+    let update = std::env::var_os(UPDATE_SNAPSHOTS).is_some_and(|value| value != "0");
+
+    if update {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).unwrap();
+        }
+        std::fs::write(path, snapshot).unwrap();
+        return;
+    }
+    if !path.exists() {
+        panic!(
+            "{} does not exist, the buffer was:\n{}Run with {}=1 to create it.",
+            path.display(),
+            snapshot,
+            UPDATE_SNAPSHOTS
+        );
+    }
+
+    let golden = std::fs::read_to_string(path).unwrap();
+    if golden.replace("\r\n", "\n") == snapshot {
+        return;
+    }
+
+    let report = match Buffer::from_snapshot(&golden) {
+        Ok(mut expected) => {
+            expected.area.x = buffer.area.x;
+            expected.area.y = buffer.area.y;
+            compare(&expected, buffer).unwrap_or_default()
+        }
+        Err(err) => format!("{err}\n"),
+    };
+    panic!(
+        "{} does not match.\n{}Run with {}=1 to update it.",
+        path.display(),
+        report,
+        UPDATE_SNAPSHOTS
+    );
+}
+
+/// Compare a buffer against `tests/snapshots/<name>.snap` in the calling crate.
+/// Set `WINTER_UPDATE_SNAPSHOTS=1` to update the file.
+#[macro_export]
+macro_rules! assert_snapshot {
+    ($name:expr, $buffer:expr) => {
+        $crate::buffer::assert_snapshot(
+            std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+                .join("tests")
+                .join("snapshots")
+                .join(format!("{}.snap", $name)),
+            &$buffer,
+        )
+    };
+}
@@ -0,0 +1,71 @@
+use crate::layout::Rect;
+use std::fmt::Display;
+
+#[derive(Debug)]
+pub enum WinterError {
+    Io(std::io::Error),
+    ///Standard input or output is not a terminal, e.g. the program was started from a pipe or a service.
+    NotATty,
+    OutOfBounds {
+        x: u16,
+        y: u16,
+        area: Rect,
+    },
+    InvalidLayout(&'static str),
+    ///A color name that isn't one of the `Color` variants.
+    InvalidColor(String),
+    ///A `Buffer::snapshot` that couldn't be read back.
+    InvalidSnapshot(String),
+    ///A key binding like `ctrl+c` that couldn't be parsed.
+    InvalidKeyBinding(String),
+    ///A keymap config line that couldn't be parsed, lines start at 1.
+    InvalidKeymap {
+        line: usize,
+        reason: String,
+    },
+}
+
+impl WinterError {
+    ///Wraps `std::io::Error::last_os_error`.
+    pub fn last_os_error() -> Self {
+        WinterError::Io(std::io::Error::last_os_error())
+    }
+}
+
+impl Display for WinterError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WinterError::Io(err) => write!(f, "{err:#}"),
+            WinterError::NotATty => write!(f, "Standard input or output is not a terminal."),
+            WinterError::OutOfBounds { x, y, area } => write!(
+                f,
+                "Trying to access position outside the buffer: x={}, y={}, area={:?}",
+                x, y, area
+            ),
+            WinterError::InvalidLayout(err) => write!(f, "{err}"),
+            WinterError::InvalidColor(color) => write!(f, "Unknown color: {color}"),
+            WinterError::InvalidSnapshot(reason) => write!(f, "Invalid snapshot: {reason}"),
+            WinterError::InvalidKeyBinding(binding) => {
+                write!(f, "Invalid key binding: {binding:?}")
+            }
+            WinterError::InvalidKeymap { line, reason } => {
+                write!(f, "Invalid keymap on line {line}: {reason}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for WinterError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            WinterError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for WinterError {
+    fn from(err: std::io::Error) -> Self {
+        WinterError::Io(err)
+    }
+}
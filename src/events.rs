@@ -0,0 +1,217 @@
+use crate::*;
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{channel, Receiver, Sender},
+        Arc, Mutex,
+    },
+    thread::{self, JoinHandle},
+    time::Instant,
+};
+
+///How long the input thread blocks before checking if it was paused or dropped.
+const INPUT_TIMEOUT: Duration = Duration::from_millis(50);
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Message<M> {
+    Input(Event, KeyState),
+    /// Sent with `Waker::send`.
+    User(M),
+    /// Every `Events::tick_rate`.
+    Tick,
+    /// Sent with `Waker::wake`.
+    Wake,
+}
+
+/// Wakes the thread waiting on `Events` and optionally hands it a message.
+#[derive(Debug)]
+pub struct Waker<M> {
+    sender: Sender<Message<M>>,
+}
+
+impl<M> Clone for Waker<M> {
+    fn clone(&self) -> Self {
+        Self {
+            sender: self.sender.clone(),
+        }
+    }
+}
+
+impl<M> Waker<M> {
+    /// Returns false once the `Events` has been dropped.
+    pub fn send(&self, message: M) -> bool {
+        self.sender.send(Message::User(message)).is_ok()
+    }
+    pub fn wake(&self) -> bool {
+        self.sender.send(Message::Wake).is_ok()
+    }
+}
+
+#[derive(Debug)]
+struct Shared {
+    paused: AtomicBool,
+    stopped: AtomicBool,
+    //Held by the input thread while it reads.
+    reading: Mutex<()>,
+}
+
+/// Input read on a background thread, merged with user messages and ticks.
+///
+/// Input events still need to go through `Winter::handle` so Ctrl+Z works.
+/// Anything else that reads stdin, like an inline viewport asking for the cursor
+/// position, should happen between `pause` and `resume`.
+///
+/// ```
+/// use winter::{events::*, *};
+///
+/// let mut backend = WriterBackend::new(Vec::new(), 10, 2);
+/// let a: Event = KeyEvent::new(KeyCode::Char('a'), KeyState(0)).into();
+/// backend.push_event(a.clone(), KeyState(0));
+///
+/// let mut events: Events<&str> = Events::with_backend(backend);
+/// let waker = events.waker();
+/// std::thread::spawn(move || waker.send("done")).join().unwrap();
+///
+/// let mut messages = vec![events.recv(), events.recv()];
+/// messages.sort_by_key(|m| matches!(m, Message::User(_)));
+/// assert_eq!(
+///     messages,
+///     [Message::Input(a, KeyState(0)), Message::User("done")]
+/// );
+/// ```
+#[derive(Debug)]
+pub struct Events<M> {
+    sender: Sender<Message<M>>,
+    receiver: Receiver<Message<M>>,
+    shared: Arc<Shared>,
+    thread: Option<JoinHandle<()>>,
+    tick_rate: Option<Duration>,
+    last_tick: Instant,
+}
+
+impl<M: Send + 'static> Events<M> {
+    /// Read from the console. Only one thread should read input, so use
+    /// `Winter::poll` or this, not both.
+    pub fn new() -> Self {
+        Self::with_options(WinterOptions::new())
+    }
+    /// Read from the console set up with `options`, the same ones given to `Winter`.
+    pub fn with_options(options: WinterOptions) -> Self {
+        Self::with_backend(Console::with_options(options))
+    }
+    pub fn with_backend<B: Backend + Send + 'static>(mut backend: B) -> Self {
+        let (sender, receiver) = channel();
+        let shared = Arc::new(Shared {
+            paused: AtomicBool::new(false),
+            stopped: AtomicBool::new(false),
+            reading: Mutex::new(()),
+        });
+
+        let input = sender.clone();
+        let thread_shared = shared.clone();
+        let thread = thread::spawn(move || loop {
+            let guard = thread_shared.reading.lock().unwrap();
+            if thread_shared.stopped.load(Ordering::Acquire) {
+                return;
+            }
+            if thread_shared.paused.load(Ordering::Acquire) {
+                drop(guard);
+                thread::sleep(INPUT_TIMEOUT);
+                continue;
+            }
+            let event = backend.poll(INPUT_TIMEOUT);
+            drop(guard);
+
+            match event {
+                Ok(Some((event, state))) => {
+                    if input.send(Message::Input(event, state)).is_err() {
+                        return;
+                    }
+                }
+                Ok(None) => {}
+                //The terminal is gone, there won't be any more input.
+                Err(_) => return,
+            }
+        });
+
+        Self {
+            sender,
+            receiver,
+            shared,
+            thread: Some(thread),
+            tick_rate: None,
+            last_tick: Instant::now(),
+        }
+    }
+}
+
+impl<M: Send + 'static> Default for Events<M> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<M> Events<M> {
+    pub fn tick_rate(mut self, tick_rate: Duration) -> Self {
+        self.tick_rate = Some(tick_rate);
+        self.last_tick = Instant::now();
+        self
+    }
+    pub fn waker(&self) -> Waker<M> {
+        Waker {
+            sender: self.sender.clone(),
+        }
+    }
+    /// Block until the next message.
+    pub fn recv(&mut self) -> Message<M> {
+        loop {
+            if let Some(message) = self.recv_timeout(Duration::MAX) {
+                return message;
+            }
+        }
+    }
+    /// Wait at most `timeout` for the next message.
+    pub fn recv_timeout(&mut self, timeout: Duration) -> Option<Message<M>> {
+        //Ticks come first so a flood of input can't delay them.
+        if self.tick() {
+            return Some(Message::Tick);
+        }
+        let wait = match self.tick_rate {
+            Some(tick_rate) => timeout.min(tick_rate.saturating_sub(self.last_tick.elapsed())),
+            None => timeout,
+        };
+        match self.receiver.recv_timeout(wait) {
+            Ok(message) => Some(message),
+            //`self.sender` keeps the channel open, so this is always a timeout.
+            Err(_) if self.tick() => Some(Message::Tick),
+            Err(_) => None,
+        }
+    }
+    fn tick(&mut self) -> bool {
+        match self.tick_rate {
+            Some(tick_rate) if self.last_tick.elapsed() >= tick_rate => {
+                self.last_tick = Instant::now();
+                true
+            }
+            _ => false,
+        }
+    }
+    /// Stop reading input, e.g. before `Winter::suspend` to run an editor.
+    /// Returns once the input thread is no longer reading.
+    pub fn pause(&self) {
+        self.shared.paused.store(true, Ordering::Release);
+        drop(self.shared.reading.lock());
+    }
+    pub fn resume(&self) {
+        self.shared.paused.store(false, Ordering::Release);
+    }
+}
+
+impl<M> Drop for Events<M> {
+    fn drop(&mut self) {
+        self.shared.stopped.store(true, Ordering::Release);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
@@ -0,0 +1,681 @@
+//! Incremental decoder for the bytes a VT/ANSI terminal sends as input.
+//!
+//! https://invisible-island.net/xterm/ctlseqs/ctlseqs.html
+use crate::*;
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+///How long to wait for the rest of an escape sequence before treating ESC as a key press.
+pub const ESCAPE_TIMEOUT: Duration = Duration::from_millis(25);
+
+///Terminals don't report double clicks, a second press on the same cell within this is one.
+pub const DOUBLE_CLICK_TIME: Duration = Duration::from_millis(500);
+
+///Sequences longer than this are garbage, they get dropped instead of buffered forever.
+const MAX_SEQUENCE: usize = 256;
+///Operating system commands and pastes carry data, e.g. the clipboard, so they can be much longer.
+const MAX_DATA: usize = 1 << 20;
+///How long a paste can go without new bytes before what arrived is reported on its own.
+pub const PASTE_TIMEOUT: Duration = Duration::from_secs(1);
+
+const PASTE_START: &[u8] = b"\x1b[200~";
+const PASTE_END: &[u8] = b"\x1b[201~";
+
+///Data that went over `MAX_DATA`, the rest of it is dropped up to its end marker.
+#[derive(Debug, Clone, Copy)]
+enum Skip {
+    Paste,
+    Osc,
+}
+
+impl Skip {
+    ///Index just past the end marker, if it arrived.
+    fn end(self, bytes: &[u8]) -> Option<usize> {
+        match self {
+            Skip::Paste => bytes
+                .windows(PASTE_END.len())
+                .position(|window| window == PASTE_END)
+                .map(|i| i + PASTE_END.len()),
+            Skip::Osc => bytes.iter().enumerate().find_map(|(i, &b)| match b {
+                0x07 => Some(i + 1),
+                0x1b => match bytes.get(i + 1) {
+                    Some(b'\\') => Some(i + 2),
+                    _ => Some(i),
+                },
+                _ => None,
+            }),
+        }
+    }
+}
+
+///False if a paste or operating system command can't have ended in `bytes`, because only
+///the first `scanned` of them were there before and had no end marker.
+fn data_ended(bytes: &[u8], scanned: usize) -> bool {
+    let from = scanned.saturating_sub(PASTE_END.len());
+    if scanned == 0 || from >= bytes.len() {
+        return true;
+    }
+    if bytes.starts_with(PASTE_START) {
+        bytes[from..]
+            .windows(PASTE_END.len())
+            .any(|window| window == PASTE_END)
+    } else {
+        bytes[from.max(2)..].iter().any(|&b| b == 0x07 || b == 0x1b)
+    }
+}
+
+///Trailing bytes that could be the start of an end marker split across reads.
+fn marker_tail(bytes: &[u8]) -> usize {
+    bytes
+        .iter()
+        .rev()
+        .take(PASTE_END.len() - 1)
+        .position(|&b| b == 0x1b)
+        .map_or(0, |i| i + 1)
+}
+
+enum Parsed {
+    ///Bytes consumed and the event they decoded to, if any.
+    Event(usize, Option<(Event, KeyState)>),
+    ///Need more bytes to decide.
+    Incomplete,
+}
+
+/// Turns a terminal byte stream into `Event`s.
+///
+/// Bytes can arrive split at any point, partial sequences are kept until the rest arrives.
+/// A lone ESC is only reported once `escape_timeout` passes without a follow up byte.
+///
+/// ```
+/// use winter::*;
+///
+/// let mut parser = InputParser::new();
+/// parser.feed(b"\x1b[1;5A\x1b[<0;3;4M");
+/// let up = KeyEvent::new(KeyCode::Up, KeyState(CONTROL));
+/// assert_eq!(parser.next(), Some((up.into(), KeyState(CONTROL))));
+/// assert_eq!(
+///     parser.next(),
+///     Some((
+///         Event::Mouse(MouseEvent {
+///             kind: MouseKind::Down,
+///             button: Some(MouseButton::Left),
+///             x: 2,
+///             y: 3,
+///             modifiers: KeyState(0),
+///         }),
+///         KeyState(0)
+///     ))
+/// );
+/// assert_eq!(parser.next(), None);
+/// ```
+#[derive(Debug, Clone)]
+pub struct InputParser {
+    pub escape_timeout: Duration,
+    buffer: Vec<u8>,
+    events: VecDeque<(Event, KeyState)>,
+    ///When the incomplete bytes at the front of `buffer` arrived, for a paste when the latest did.
+    pending_since: Option<Instant>,
+    ///The last press that could be the first half of a double click.
+    last_click: Option<(MouseEvent, Instant)>,
+    ///Set after data went over `MAX_DATA` until its end marker arrives.
+    skip: Option<Skip>,
+    ///How much of the data at the front of `buffer` was already searched for its end.
+    scanned: usize,
+}
+
+impl Default for InputParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl InputParser {
+    pub fn new() -> Self {
+        Self {
+            escape_timeout: ESCAPE_TIMEOUT,
+            buffer: Vec::new(),
+            events: VecDeque::new(),
+            pending_since: None,
+            last_click: None,
+            skip: None,
+            scanned: 0,
+        }
+    }
+    pub fn feed(&mut self, bytes: &[u8]) {
+        self.feed_at(bytes, Instant::now());
+    }
+    pub fn feed_at(&mut self, bytes: &[u8], now: Instant) {
+        self.buffer.extend_from_slice(bytes);
+        self.parse(false, now);
+
+        if self.buffer.is_empty() && self.skip.is_none() {
+            self.pending_since = None;
+        } else if self.pending_since.is_none() || self.in_data() {
+            //Data only times out once the terminal stops sending it.
+            self.pending_since = Some(now);
+        }
+    }
+    /// The next decoded event. Pending escape sequences are resolved if they timed out.
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<(Event, KeyState)> {
+        self.next_at(Instant::now())
+    }
+    pub fn next_at(&mut self, now: Instant) -> Option<(Event, KeyState)> {
+        if self.events.is_empty() && self.timeout(now) == Some(Duration::ZERO) {
+            self.flush();
+        }
+        self.events.pop_front()
+    }
+    /// Resolve any incomplete input right now, a lone ESC becomes `Event::Escape`.
+    pub fn flush(&mut self) {
+        self.parse(true, Instant::now());
+        self.pending_since = None;
+    }
+    /// How long until the pending input times out, `None` if nothing is pending.
+    pub fn timeout(&self, now: Instant) -> Option<Duration> {
+        let timeout = match self.in_data() {
+            true => PASTE_TIMEOUT,
+            false => self.escape_timeout,
+        };
+        self.pending_since
+            .map(|since| timeout.saturating_sub(now.duration_since(since)))
+    }
+    /// Queue an event that didn't come from the byte stream.
+    pub fn push(&mut self, event: Event, state: KeyState) {
+        self.events.push_back((event, state));
+    }
+    /// True if there are decoded events waiting.
+    pub fn has_events(&self) -> bool {
+        !self.events.is_empty()
+    }
+
+    ///True while a paste, an operating system command or skipped data is still arriving.
+    fn in_data(&self) -> bool {
+        self.buffer.starts_with(PASTE_START)
+            || self.buffer.starts_with(b"\x1b]")
+            || self.skip.is_some()
+    }
+    fn parse(&mut self, force: bool, now: Instant) {
+        let mut start = 0;
+        while start < self.buffer.len() {
+            let bytes = &self.buffer[start..];
+            if let Some(skip) = self.skip {
+                match skip.end(bytes) {
+                    Some(end) => {
+                        start += end;
+                        self.skip = None;
+                        continue;
+                    }
+                    None if force => start = self.buffer.len(),
+                    None => start = self.buffer.len() - marker_tail(bytes),
+                }
+                break;
+            }
+            //Only the new bytes can end data that was incomplete before.
+            let parsed = match start == 0 && !data_ended(bytes, self.scanned) {
+                true => Parsed::Incomplete,
+                false => parse(bytes),
+            };
+            match parsed {
+                Parsed::Event(len, event) => {
+                    start += len;
+                    if let Some(event) = event {
+                        let event = self.double_click(event, now);
+                        self.events.push_back(event);
+                    }
+                }
+                Parsed::Incomplete if force && bytes.starts_with(PASTE_START) => {
+                    //The end marker never came, keep what did.
+                    let text = paste_text(&bytes[PASTE_START.len()..]);
+                    self.events.push_back((Event::Paste(text), KeyState(0)));
+                    start = self.buffer.len();
+                }
+                Parsed::Incomplete if force => {
+                    //Give up on the sequence, ESC on its own is a key press.
+                    if bytes[0] == 0x1b {
+                        self.events.extend(key(KeyCode::Escape, KeyState(0)));
+                    }
+                    start += 1;
+                }
+                Parsed::Incomplete if bytes.len() > MAX_DATA && bytes.starts_with(PASTE_START) => {
+                    //Too long to keep, report what arrived and drop the rest.
+                    let end = bytes.len() - marker_tail(bytes);
+                    let text = paste_text(&bytes[PASTE_START.len()..end]);
+                    self.events.push_back((Event::Paste(text), KeyState(0)));
+                    self.skip = Some(Skip::Paste);
+                    start += end;
+                }
+                Parsed::Incomplete if bytes.len() > MAX_DATA && bytes.starts_with(b"\x1b]") => {
+                    self.skip = Some(Skip::Osc);
+                    start += bytes.len() - marker_tail(bytes);
+                }
+                Parsed::Incomplete
+                    if bytes.len() > MAX_SEQUENCE
+                        && !bytes.starts_with(b"\x1b]")
+                        && !bytes.starts_with(PASTE_START) =>
+                {
+                    start += 1
+                }
+                Parsed::Incomplete => break,
+            }
+        }
+        self.buffer.drain(..start);
+        if force {
+            self.skip = None;
+        }
+        self.scanned = match self.skip.is_none() && self.in_data() {
+            true => self.buffer.len(),
+            false => 0,
+        };
+    }
+    fn double_click(
+        &mut self,
+        (event, state): (Event, KeyState),
+        now: Instant,
+    ) -> (Event, KeyState) {
+        let Event::Mouse(mut mouse) = event else {
+            return (event, state);
+        };
+        if mouse.kind != MouseKind::Down {
+            return (event, state);
+        }
+        match self.last_click.take() {
+            Some((last, at))
+                if last.button == mouse.button
+                    && (last.x, last.y) == (mouse.x, mouse.y)
+                    && now.duration_since(at) < DOUBLE_CLICK_TIME =>
+            {
+                mouse.kind = MouseKind::DoubleClick
+            }
+            _ => self.last_click = Some((mouse, now)),
+        }
+        (Event::Mouse(mouse), state)
+    }
+}
+
+fn parse(bytes: &[u8]) -> Parsed {
+    let (code, state) = match bytes[0] {
+        0x1b => return parse_escape(bytes),
+        b'\r' | b'\n' => (KeyCode::Enter, 0),
+        b'\t' => (KeyCode::Tab, 0),
+        0x7f | 0x08 => (KeyCode::Backspace, 0),
+        0x00 => (KeyCode::Char(' '), CONTROL),
+        0x01..=0x1a => (KeyCode::Char((bytes[0] - 1 + b'a') as char), CONTROL),
+        0x1c..=0x1f => (KeyCode::Unknown(bytes[0] as u16), 0),
+        b'A'..=b'Z' => (KeyCode::Char(bytes[0] as char), SHIFT),
+        0x20..=0x7e => (KeyCode::Char(bytes[0] as char), 0),
+        _ => return parse_utf8(bytes),
+    };
+    Parsed::Event(1, key(code, KeyState(state)))
+}
+
+fn key(code: KeyCode, state: KeyState) -> Option<(Event, KeyState)> {
+    key_kind(code, state, KeyKind::Press)
+}
+
+fn key_kind(code: KeyCode, state: KeyState, kind: KeyKind) -> Option<(Event, KeyState)> {
+    let key = KeyEvent {
+        code,
+        modifiers: state,
+        kind,
+    };
+    Some((Event::Key(key), state))
+}
+
+fn parse_utf8(bytes: &[u8]) -> Parsed {
+    //The leading byte holds the length.
+    let len = match bytes[0] {
+        0xc0..=0xdf => 2,
+        0xe0..=0xef => 3,
+        0xf0..=0xf7 => 4,
+        _ => return Parsed::Event(1, None),
+    };
+    if bytes.len() < len {
+        //Stop waiting early if the continuation bytes are already wrong.
+        if bytes[1..].iter().any(|b| b & 0xc0 != 0x80) {
+            return Parsed::Event(1, None);
+        }
+        return Parsed::Incomplete;
+    }
+    match std::str::from_utf8(&bytes[..len]) {
+        Ok(str) => {
+            let char = str.chars().next().unwrap();
+            Parsed::Event(len, key(KeyCode::Char(char), KeyState(0)))
+        }
+        Err(_) => Parsed::Event(1, None),
+    }
+}
+
+fn parse_escape(bytes: &[u8]) -> Parsed {
+    match bytes.get(1) {
+        None => Parsed::Incomplete,
+        Some(b'[') => parse_csi(bytes),
+        Some(b'O') => parse_ss3(bytes),
+        Some(b']') => parse_osc(bytes),
+        //Double escape, the first one is a key press.
+        Some(0x1b) => Parsed::Event(1, key(KeyCode::Escape, KeyState(0))),
+        //Alt + key
+        Some(_) => match parse(&bytes[1..]) {
+            Parsed::Event(len, event) => Parsed::Event(len + 1, event.map(with_alt)),
+            Parsed::Incomplete => Parsed::Incomplete,
+        },
+    }
+}
+
+fn with_alt((event, state): (Event, KeyState)) -> (Event, KeyState) {
+    let state = KeyState(state.0 | ALT);
+    match event {
+        Event::Key(key) => (
+            Event::Key(KeyEvent {
+                modifiers: state,
+                ..key
+            }),
+            state,
+        ),
+        event => (event, state),
+    }
+}
+
+fn parse_ss3(bytes: &[u8]) -> Parsed {
+    let Some(&byte) = bytes.get(2) else {
+        return Parsed::Incomplete;
+    };
+    let code = match byte {
+        b'A' => KeyCode::Up,
+        b'B' => KeyCode::Down,
+        b'C' => KeyCode::Right,
+        b'D' => KeyCode::Left,
+        b'H' => KeyCode::Home,
+        b'F' => KeyCode::End,
+        b'M' => KeyCode::Enter,
+        b'P'..=b'S' => KeyCode::Function(byte - b'P' + 1),
+        //The keypad in application mode.
+        b'j' => KeyCode::Char('*'),
+        b'k' => KeyCode::Char('+'),
+        b'l' => KeyCode::Char(','),
+        b'm' => KeyCode::Char('-'),
+        b'n' => KeyCode::Char('.'),
+        b'o' => KeyCode::Char('/'),
+        b'p'..=b'y' => KeyCode::Char((byte - b'p' + b'0') as char),
+        b'X' => KeyCode::Char('='),
+        _ => return Parsed::Event(3, None),
+    };
+    Parsed::Event(3, key(code, KeyState(0)))
+}
+
+/// `ESC ] command ST`, ST is `ESC \` or BEL.
+fn parse_osc(bytes: &[u8]) -> Parsed {
+    let Some(end) = bytes[2..]
+        .iter()
+        .position(|&b| b == 0x07 || b == 0x1b)
+        .map(|i| i + 2)
+    else {
+        return Parsed::Incomplete;
+    };
+    let len = match bytes[end] {
+        0x07 => end + 1,
+        _ => match bytes.get(end + 1) {
+            None => return Parsed::Incomplete,
+            Some(b'\\') => end + 2,
+            //Not terminated, drop it.
+            Some(_) => return Parsed::Event(end, None),
+        },
+    };
+
+    //`52 ; selection ; base64`
+    let command = String::from_utf8_lossy(&bytes[2..end]);
+    let mut fields = command.splitn(3, ';');
+    let event = match (fields.next(), fields.next(), fields.next()) {
+        (Some("52"), Some(_), Some(data)) => base64::decode(data).map(|text| {
+            (
+                Event::Clipboard(String::from_utf8_lossy(&text).into()),
+                KeyState(0),
+            )
+        }),
+        _ => None,
+    };
+    Parsed::Event(len, event)
+}
+
+/// `ESC [ params intermediates final`
+fn parse_csi(bytes: &[u8]) -> Parsed {
+    let mut end = 2;
+    let final_byte = loop {
+        let Some(&byte) = bytes.get(end) else {
+            return Parsed::Incomplete;
+        };
+        match byte {
+            0x20..=0x3f => end += 1,
+            0x40..=0x7e => break byte,
+            //Not a valid sequence, drop what we have so far.
+            _ => return Parsed::Event(end, None),
+        }
+    };
+    let len = end + 1;
+    let params = &bytes[2..end];
+
+    if let Some(b'<') = params.first() {
+        return Parsed::Event(len, decode_sgr_mouse(&params[1..], final_byte));
+    }
+
+    let Ok(params) = std::str::from_utf8(params) else {
+        return Parsed::Event(len, None);
+    };
+    //Replies like `CSI ? flags u` aren't keys.
+    if params.starts_with(['?', '>', '=']) {
+        return Parsed::Event(len, None);
+    }
+    //`number:alternates ; modifiers:event ; text`, the sub parameters come from the kitty protocol.
+    let params: Vec<Vec<Option<u32>>> = params
+        .split(';')
+        .map(|param| param.split(':').map(|sub| sub.parse().ok()).collect())
+        .collect();
+    let param = |i: usize, j: usize| params.get(i).and_then(|p| p.get(j).copied().flatten());
+    let number = param(0, 0).unwrap_or(1);
+    let state = modifier_state(param(1, 0).unwrap_or(1));
+    let kind = match param(1, 1) {
+        Some(2) => KeyKind::Repeat,
+        Some(3) => KeyKind::Release,
+        _ => KeyKind::Press,
+    };
+
+    let code = match final_byte {
+        b'A' => KeyCode::Up,
+        b'B' => KeyCode::Down,
+        b'C' => KeyCode::Right,
+        b'D' => KeyCode::Left,
+        b'H' => KeyCode::Home,
+        b'F' => KeyCode::End,
+        b'Z' => {
+            let state = KeyState(state.0 | SHIFT);
+            return Parsed::Event(len, key_kind(KeyCode::Tab, state, kind));
+        }
+        b'I' => return Parsed::Event(len, Some((Event::FocusGained, KeyState(0)))),
+        b'O' => return Parsed::Event(len, Some((Event::FocusLost, KeyState(0)))),
+        b'P'..=b'S' => KeyCode::Function(final_byte - b'P' + 1),
+        b'u' => match kitty_key(number, param(0, 1), state) {
+            Some(code) => code,
+            None => return Parsed::Event(len, None),
+        },
+        b'~' => match number {
+            200 => return parse_paste(bytes, len),
+            1 | 7 => KeyCode::Home,
+            2 => KeyCode::Insert,
+            3 => KeyCode::Delete,
+            4 | 8 => KeyCode::End,
+            5 => KeyCode::PageUp,
+            6 => KeyCode::PageDown,
+            11..=15 => KeyCode::Function((number - 10) as u8),
+            17..=21 => KeyCode::Function((number - 11) as u8),
+            23..=26 => KeyCode::Function((number - 12) as u8),
+            28 | 29 => KeyCode::Function((number - 13) as u8),
+            31..=34 => KeyCode::Function((number - 14) as u8),
+            _ => return Parsed::Event(len, None),
+        },
+        _ => return Parsed::Event(len, None),
+    };
+    Parsed::Event(len, key_kind(code, state, kind))
+}
+
+///Kitty key numbers are the unshifted code point, or one from the private use area.
+///https://sw.kovidgoyal.net/kitty/keyboard-protocol/#functional-key-definitions
+fn kitty_key(number: u32, shifted: Option<u32>, state: KeyState) -> Option<KeyCode> {
+    let code = match number {
+        9 => KeyCode::Tab,
+        13 => KeyCode::Enter,
+        27 => KeyCode::Escape,
+        8 | 127 => KeyCode::Backspace,
+        57376..=57398 => KeyCode::Function((number - 57376 + 13) as u8),
+        //The keypad.
+        57399..=57408 => KeyCode::Char(char::from_digit(number - 57399, 10)?),
+        57409 => KeyCode::Char('.'),
+        57410 => KeyCode::Char('/'),
+        57411 => KeyCode::Char('*'),
+        57412 => KeyCode::Char('-'),
+        57413 => KeyCode::Char('+'),
+        57414 => KeyCode::Enter,
+        57415 => KeyCode::Char('='),
+        57416 => KeyCode::Char(','),
+        57417 => KeyCode::Left,
+        57418 => KeyCode::Right,
+        57419 => KeyCode::Up,
+        57420 => KeyCode::Down,
+        57421 => KeyCode::PageUp,
+        57422 => KeyCode::PageDown,
+        57423 => KeyCode::Home,
+        57424 => KeyCode::End,
+        57425 => KeyCode::Insert,
+        57426 => KeyCode::Delete,
+        57428 => KeyCode::Media(MediaKey::Play),
+        57429 => KeyCode::Media(MediaKey::Pause),
+        57430 => KeyCode::Media(MediaKey::PlayPause),
+        57432 => KeyCode::Media(MediaKey::Stop),
+        57433 => KeyCode::Media(MediaKey::FastForward),
+        57434 => KeyCode::Media(MediaKey::Rewind),
+        57435 => KeyCode::Media(MediaKey::Next),
+        57436 => KeyCode::Media(MediaKey::Previous),
+        57437 => KeyCode::Media(MediaKey::Record),
+        57438 => KeyCode::Media(MediaKey::VolumeDown),
+        57439 => KeyCode::Media(MediaKey::VolumeUp),
+        57440 => KeyCode::Media(MediaKey::Mute),
+        57441 | 57447 => KeyCode::Shift,
+        57442 | 57448 => KeyCode::Control,
+        57443 | 57449 => KeyCode::Alt,
+        //Lock keys, super, hyper and the rest don't have a `KeyCode`.
+        57344..=63743 => return None,
+        _ => {
+            //The shifted key is only there with the alternate keys flag.
+            let char = char::from_u32(shifted.filter(|_| state.shift()).unwrap_or(number))?;
+            match state.shift() {
+                true => KeyCode::Char(char.to_ascii_uppercase()),
+                false => KeyCode::Char(char),
+            }
+        }
+    };
+    Some(code)
+}
+
+/// `CSI 200 ~ text CSI 201 ~`, `start` is the length of the first marker.
+fn parse_paste(bytes: &[u8], start: usize) -> Parsed {
+    let Some(end) = bytes[start..]
+        .windows(PASTE_END.len())
+        .position(|window| window == PASTE_END)
+        .map(|i| i + start)
+    else {
+        return Parsed::Incomplete;
+    };
+    let text = paste_text(&bytes[start..end]);
+    Parsed::Event(
+        end + PASTE_END.len(),
+        Some((Event::Paste(text), KeyState(0))),
+    )
+}
+
+///Terminals send Enter as `\r`, pasted line endings are turned into `\n`.
+pub fn paste_text(bytes: &[u8]) -> String {
+    String::from_utf8_lossy(bytes)
+        .replace("\r\n", "\n")
+        .replace('\r', "\n")
+}
+
+///Modifier parameters are encoded as `1 + (shift | alt << 1 | control << 2)`,
+///the kitty protocol adds super, hyper, meta, caps lock and num lock above those.
+fn modifier_state(param: u32) -> KeyState {
+    let bits = param.saturating_sub(1);
+    let mut state = 0;
+    for (bit, modifier) in [
+        (1, SHIFT),
+        (2, ALT),
+        (4, CONTROL),
+        (8, SUPER),
+        (16, HYPER),
+        (32, META),
+        (64, CAPS_LOCK),
+        (128, NUM_LOCK),
+    ] {
+        if bits & bit != 0 {
+            state |= modifier;
+        }
+    }
+    KeyState(state)
+}
+
+///`CSI < button ; x ; y M` for presses and motion, `m` for releases.
+fn decode_sgr_mouse(params: &[u8], final_byte: u8) -> Option<(Event, KeyState)> {
+    let mut params = std::str::from_utf8(params)
+        .ok()?
+        .split(';')
+        .map(|param| param.parse::<u16>().ok());
+    let code = params.next()??;
+    let x = params.next()??.saturating_sub(1);
+    let y = params.next()??.saturating_sub(1);
+
+    let mut state = 0;
+    if code & 4 != 0 {
+        state |= SHIFT;
+    }
+    if code & 8 != 0 {
+        state |= ALT;
+    }
+    if code & 16 != 0 {
+        state |= CONTROL;
+    }
+
+    //The low two bits are the button, 3 is none. 32 is motion and 64 the wheel.
+    let code = code & !(4 | 8 | 16);
+    let button = match code & 3 {
+        0 => Some(MouseButton::Left),
+        1 => Some(MouseButton::Middle),
+        2 => Some(MouseButton::Right),
+        _ => None,
+    };
+    let (kind, button) = match code & !3 {
+        64 => {
+            let kind = match code & 3 {
+                0 => MouseKind::ScrollUp,
+                1 => MouseKind::ScrollDown,
+                2 => MouseKind::ScrollLeft,
+                _ => MouseKind::ScrollRight,
+            };
+            (kind, None)
+        }
+        32 if button.is_some() => (MouseKind::Drag, button),
+        32 => (MouseKind::Move, None),
+        0 if button.is_none() => return None,
+        0 if final_byte == b'm' => (MouseKind::Up, button),
+        0 => (MouseKind::Down, button),
+        //Buttons past the wheel, e.g. back and forward.
+        _ => return None,
+    };
+
+    let mouse = MouseEvent {
+        kind,
+        button,
+        x,
+        y,
+        modifiers: KeyState(state),
+    };
+    Some((Event::Mouse(mouse), KeyState(state)))
+}
@@ -0,0 +1,562 @@
+//! Key bindings written as text, e.g. `ctrl+c`, and maps from them to actions.
+use crate::*;
+use std::{
+    collections::VecDeque,
+    fmt::Display,
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+///In the order they are written out.
+const MODIFIERS: &[(&str, u32)] = &[
+    ("ctrl", CONTROL),
+    ("alt", ALT),
+    ("shift", SHIFT),
+    ("super", SUPER),
+    ("hyper", HYPER),
+    ("meta", META),
+];
+const MODIFIER_ALIASES: &[(&str, u32)] = &[("control", CONTROL)];
+
+const KEYS: &[(&str, KeyCode)] = &[
+    ("enter", KeyCode::Enter),
+    ("tab", KeyCode::Tab),
+    ("backspace", KeyCode::Backspace),
+    ("esc", KeyCode::Escape),
+    ("space", KeyCode::Char(' ')),
+    ("up", KeyCode::Up),
+    ("down", KeyCode::Down),
+    ("left", KeyCode::Left),
+    ("right", KeyCode::Right),
+    ("home", KeyCode::Home),
+    ("end", KeyCode::End),
+    ("pageup", KeyCode::PageUp),
+    ("pagedown", KeyCode::PageDown),
+    ("insert", KeyCode::Insert),
+    ("delete", KeyCode::Delete),
+    ("play", KeyCode::Media(MediaKey::Play)),
+    ("pause", KeyCode::Media(MediaKey::Pause)),
+    ("playpause", KeyCode::Media(MediaKey::PlayPause)),
+    ("stop", KeyCode::Media(MediaKey::Stop)),
+    ("fastforward", KeyCode::Media(MediaKey::FastForward)),
+    ("rewind", KeyCode::Media(MediaKey::Rewind)),
+    ("next", KeyCode::Media(MediaKey::Next)),
+    ("previous", KeyCode::Media(MediaKey::Previous)),
+    ("record", KeyCode::Media(MediaKey::Record)),
+    ("volumeup", KeyCode::Media(MediaKey::VolumeUp)),
+    ("volumedown", KeyCode::Media(MediaKey::VolumeDown)),
+    ("mute", KeyCode::Media(MediaKey::Mute)),
+];
+const KEY_ALIASES: &[(&str, KeyCode)] = &[
+    ("escape", KeyCode::Escape),
+    ("return", KeyCode::Enter),
+    ("del", KeyCode::Delete),
+];
+
+/// A key and the modifiers held with it.
+///
+/// Letters are kept lowercase with `SHIFT`, so `A` and `shift+a` are the same binding.
+/// Other characters already say whether shift was held, so it's dropped for them.
+///
+/// ```
+/// use winter::{keymap::KeyBinding, *};
+///
+/// let binding: KeyBinding = "ctrl+shift+tab".parse()?;
+/// assert_eq!(binding.code, KeyCode::Tab);
+/// assert_eq!(binding.modifiers, KeyState(CONTROL | SHIFT));
+/// assert_eq!(binding.to_string(), "ctrl+shift+tab");
+///
+/// let event = KeyEvent::new(KeyCode::Char('A'), KeyState(SHIFT));
+/// assert!("shift+a".parse::<KeyBinding>()?.matches(&event.into(), KeyState(SHIFT)));
+/// # Ok::<(), WinterError>(())
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyBinding {
+    pub code: KeyCode,
+    pub modifiers: KeyState,
+}
+
+impl KeyBinding {
+    pub fn new(code: KeyCode, modifiers: KeyState) -> Self {
+        let mask = MODIFIERS.iter().fold(0, |mask, (_, bit)| mask | bit);
+        let mut modifiers = modifiers.0 & mask;
+        let code = match code {
+            KeyCode::Char(c) if c.is_alphabetic() => {
+                let mut lower = c.to_lowercase();
+                match (lower.next(), lower.next()) {
+                    (Some(lower), None) if lower != c => {
+                        modifiers |= SHIFT;
+                        KeyCode::Char(lower)
+                    }
+                    _ => code,
+                }
+            }
+            KeyCode::Char(_) => {
+                modifiers &= !SHIFT;
+                code
+            }
+            code => code,
+        };
+        Self {
+            code,
+            modifiers: KeyState(modifiers),
+        }
+    }
+    /// The binding for a key press or repeat, `None` for releases and other events.
+    pub fn from_event(event: &Event, state: KeyState) -> Option<Self> {
+        match event {
+            Event::Key(key) if key.kind != KeyKind::Release => Some(Self::new(key.code, state)),
+            _ => None,
+        }
+    }
+    pub fn matches(&self, event: &Event, state: KeyState) -> bool {
+        Self::from_event(event, state) == Some(*self)
+    }
+}
+
+impl FromStr for KeyBinding {
+    type Err = WinterError;
+
+    /// Modifiers are `ctrl`, `alt` and `shift` joined with `+`, the key comes last.
+    /// Names are case insensitive, single characters are not.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || WinterError::InvalidKeyBinding(s.to_string());
+
+        //`ctrl++` is Ctrl and the plus key.
+        let (modifiers, key) = match s.strip_suffix("++") {
+            Some(modifiers) => (Some(modifiers), "+"),
+            None if s == "+" => (None, "+"),
+            None => match s.rsplit_once('+') {
+                Some((modifiers, key)) => (Some(modifiers), key),
+                None => (None, s),
+            },
+        };
+
+        let mut state = 0;
+        for modifier in modifiers.into_iter().flat_map(|m| m.split('+')) {
+            let modifier = modifier.to_ascii_lowercase();
+            let (_, bit) = MODIFIERS
+                .iter()
+                .chain(MODIFIER_ALIASES)
+                .find(|(name, _)| *name == modifier)
+                .ok_or_else(invalid)?;
+            state |= bit;
+        }
+
+        let mut chars = key.chars();
+        let name = key.to_ascii_lowercase();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match KEYS.iter().chain(KEY_ALIASES).find(|(n, _)| *n == name) {
+                Some((_, code)) => *code,
+                None => match name.strip_prefix('f').map(str::parse) {
+                    Some(Ok(n @ 1..=24)) => KeyCode::Function(n),
+                    _ => return Err(invalid()),
+                },
+            },
+        };
+        Ok(Self::new(code, KeyState(state)))
+    }
+}
+
+/// Keys without a name, e.g. `KeyCode::Unknown`, don't parse back.
+impl Display for KeyBinding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (name, bit) in MODIFIERS {
+            if self.modifiers.0 & bit != 0 {
+                write!(f, "{name}+")?;
+            }
+        }
+        match (KEYS.iter().find(|(_, code)| *code == self.code), self.code) {
+            (Some((name, _)), _) => write!(f, "{name}"),
+            (None, KeyCode::Char(c)) => write!(f, "{c}"),
+            (None, KeyCode::Function(n)) => write!(f, "f{n}"),
+            (None, code) => write!(f, "{}", format!("{code:?}").to_ascii_lowercase()),
+        }
+    }
+}
+
+/// Key bindings for a set of actions, e.g. an enum of everything a view can do.
+///
+/// The config format has one action per line followed by its bindings:
+/// `action = binding binding`. Empty lines and lines starting with `#` are skipped.
+///
+/// ```
+/// use winter::{keymap::*, *};
+///
+/// let mut keymap = keymap()
+///     .bind("space".parse()?, String::from("pause"))
+///     .bind("ctrl+right".parse()?, String::from("seek"));
+/// keymap.load("# Rebound by the user\nseek = l shift+right\n")?;
+///
+/// let l = KeyEvent::new(KeyCode::Char('l'), KeyState(0));
+/// assert_eq!(keymap.get(&l.into(), KeyState(0)), Some(&String::from("seek")));
+/// assert_eq!(keymap.to_config(), "pause = space\nseek = l shift+right\n");
+/// # Ok::<(), WinterError>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Keymap<A> {
+    /// In the order they were added, a binding is only in here once.
+    pub bindings: Vec<(KeyBinding, A)>,
+}
+
+pub fn keymap<A>() -> Keymap<A> {
+    Keymap {
+        bindings: Vec::new(),
+    }
+}
+
+impl<A> Default for Keymap<A> {
+    fn default() -> Self {
+        keymap()
+    }
+}
+
+impl<A> Keymap<A> {
+    pub fn bind(mut self, binding: KeyBinding, action: A) -> Self {
+        self.insert(binding, action);
+        self
+    }
+    /// Returns the action `binding` was bound to before.
+    pub fn insert(&mut self, binding: KeyBinding, action: A) -> Option<A> {
+        match self.bindings.iter_mut().find(|(b, _)| *b == binding) {
+            Some((_, old)) => Some(std::mem::replace(old, action)),
+            None => {
+                self.bindings.push((binding, action));
+                None
+            }
+        }
+    }
+    pub fn remove(&mut self, binding: KeyBinding) -> Option<A> {
+        let index = self.bindings.iter().position(|(b, _)| *b == binding)?;
+        Some(self.bindings.remove(index).1)
+    }
+    /// The action for a key press, `None` if it isn't bound.
+    pub fn get(&self, event: &Event, state: KeyState) -> Option<&A> {
+        let binding = KeyBinding::from_event(event, state)?;
+        self.bindings
+            .iter()
+            .find(|(b, _)| *b == binding)
+            .map(|(_, action)| action)
+    }
+}
+
+impl<A: PartialEq> Keymap<A> {
+    /// Every binding for `action`, e.g. to show them in a help line.
+    pub fn bindings_for<'a>(&'a self, action: &'a A) -> impl Iterator<Item = KeyBinding> + 'a {
+        self.bindings
+            .iter()
+            .filter(move |(_, a)| a == action)
+            .map(|(binding, _)| *binding)
+    }
+}
+
+impl<A: Display + PartialEq> Keymap<A> {
+    /// The format `load` reads, actions are written in the order they were first bound.
+    pub fn to_config(&self) -> String {
+        let mut config = String::new();
+        for (i, (_, action)) in self.bindings.iter().enumerate() {
+            if self.bindings[..i].iter().any(|(_, a)| a == action) {
+                continue;
+            }
+            let bindings: Vec<String> = self
+                .bindings_for(action)
+                .map(|binding| binding.to_string())
+                .collect();
+            config.push_str(&format!("{action} = {}\n", bindings.join(" ")));
+        }
+        config
+    }
+}
+
+impl<A: FromStr + PartialEq + Clone> Keymap<A> {
+    pub fn from_config(config: &str) -> Result<Self, WinterError> {
+        let mut keymap = keymap();
+        keymap.load(config)?;
+        Ok(keymap)
+    }
+    /// Replace the bindings of every action in `config`, the other actions keep theirs.
+    /// Nothing changes if `config` has an error.
+    pub fn load(&mut self, config: &str) -> Result<(), WinterError> {
+        let mut actions = Vec::new();
+        for (i, line) in config.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid = |reason: String| WinterError::InvalidKeymap {
+                line: i + 1,
+                reason,
+            };
+
+            let Some((name, bindings)) = line.split_once('=') else {
+                return Err(invalid("expected `action = binding`".into()));
+            };
+            let name = name.trim();
+            let action =
+                A::from_str(name).map_err(|_| invalid(format!("unknown action {name:?}")))?;
+            let bindings = bindings
+                .split_whitespace()
+                .map(|binding| binding.parse().map_err(|err| invalid(format!("{err}"))))
+                .collect::<Result<Vec<KeyBinding>, _>>()?;
+            actions.push((action, bindings));
+        }
+
+        for (action, _) in &actions {
+            self.bindings.retain(|(_, a)| a != action);
+        }
+        for (action, bindings) in actions {
+            for binding in bindings {
+                self.insert(binding, action.clone());
+            }
+        }
+        Ok(())
+    }
+}
+
+///How long `Chords` waits for the next key of a chord.
+pub const CHORD_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Parse a chord like `g g` or `<space> f s`, keys are separated by spaces
+/// and can be wrapped in `<>`.
+///
+/// ```
+/// use winter::{keymap::*, *};
+///
+/// let keys = chord("<space> f ctrl+s")?;
+/// assert_eq!(keys[0], KeyBinding::new(KeyCode::Char(' '), KeyState(0)));
+/// assert_eq!(keys[2].to_string(), "ctrl+s");
+/// # Ok::<(), WinterError>(())
+/// ```
+pub fn chord(text: &str) -> Result<Vec<KeyBinding>, WinterError> {
+    let keys = text
+        .split_whitespace()
+        .map(|key| {
+            key.strip_prefix('<')
+                .and_then(|key| key.strip_suffix('>'))
+                .unwrap_or(key)
+                .parse()
+        })
+        .collect::<Result<Vec<KeyBinding>, _>>()?;
+    match keys.is_empty() {
+        true => Err(WinterError::InvalidKeyBinding(text.to_string())),
+        false => Ok(keys),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChordEvent<A> {
+    Action(A),
+    /// Input that isn't part of a chord, handle it like any other event.
+    Unmatched(Event, KeyState),
+}
+
+/// Matches sequences of key presses like `g g` to actions.
+///
+/// Keys that could still become a chord are held back until the chord is complete,
+/// another key breaks it or `chord_timeout` passes. When a chord is also the start of a
+/// longer one, e.g. `g` and `g g`, the longer one wins if its next key comes in time.
+/// Keys that turn out not to be a chord come back as `ChordEvent::Unmatched`.
+/// Everything other than key presses goes straight through, or after the held back keys
+/// so the order is kept.
+///
+/// ```
+/// use std::time::Instant;
+/// use winter::{keymap::*, *};
+///
+/// let mut chords = chords()
+///     .bind(chord("g g")?, "top")
+///     .bind(chord("g")?, "next tab");
+/// let g = KeyEvent::new(KeyCode::Char('g'), KeyState(0));
+///
+/// chords.feed(g.into(), KeyState(0));
+/// assert_eq!(chords.next(), None);
+/// assert_eq!(chords.pending(), chord("g")?);
+///
+/// chords.feed(g.into(), KeyState(0));
+/// assert_eq!(chords.next(), Some(ChordEvent::Action("top")));
+///
+/// //A lone `g` is only known once the timeout passes.
+/// chords.feed(g.into(), KeyState(0));
+/// let later = Instant::now() + CHORD_TIMEOUT;
+/// assert_eq!(chords.next_at(later), Some(ChordEvent::Action("next tab")));
+/// # Ok::<(), WinterError>(())
+/// ```
+///
+/// In an event loop, don't wait longer than `timeout` says:
+///
+/// ```no_run
+/// # use std::time::{Duration, Instant};
+/// # use winter::{keymap::*, *};
+/// # let mut winter = Winter::new()?;
+/// # let mut chords = chords().bind(chord("d d")?, "delete");
+/// loop {
+///     let timeout = chords.timeout(Instant::now()).unwrap_or(Duration::from_secs(1));
+///     if let Some((event, state)) = winter.poll_timeout(timeout)? {
+///         chords.feed(event, state);
+///     }
+///     while let Some(event) = chords.next() {
+///         match event {
+///             ChordEvent::Action(action) => println!("{action}"),
+///             ChordEvent::Unmatched(event, _) => println!("{event}"),
+///         }
+///     }
+/// }
+/// # Ok::<(), WinterError>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct Chords<A> {
+    /// In the order they were added, a chord is only in here once.
+    pub chords: Vec<(Vec<KeyBinding>, A)>,
+    pub chord_timeout: Duration,
+    ///Key presses that could still become a chord, and other events that came in after them.
+    pending: Vec<(Event, KeyState, Option<KeyBinding>)>,
+    ///When the last pending key came in.
+    pending_since: Option<Instant>,
+    events: VecDeque<ChordEvent<A>>,
+}
+
+pub fn chords<A>() -> Chords<A> {
+    Chords {
+        chords: Vec::new(),
+        chord_timeout: CHORD_TIMEOUT,
+        pending: Vec::new(),
+        pending_since: None,
+        events: VecDeque::new(),
+    }
+}
+
+impl<A> Default for Chords<A> {
+    fn default() -> Self {
+        chords()
+    }
+}
+
+impl<A> Chords<A> {
+    /// Replaces whatever action `chord` had before.
+    pub fn bind(mut self, chord: Vec<KeyBinding>, action: A) -> Self {
+        match self.chords.iter_mut().find(|(keys, _)| *keys == chord) {
+            Some((_, old)) => *old = action,
+            None => self.chords.push((chord, action)),
+        }
+        self
+    }
+    pub fn chord_timeout(mut self, chord_timeout: Duration) -> Self {
+        self.chord_timeout = chord_timeout;
+        self
+    }
+    /// The keys pressed so far of a chord that isn't complete yet.
+    pub fn pending(&self) -> Vec<KeyBinding> {
+        self.pending.iter().filter_map(|(_, _, key)| *key).collect()
+    }
+    /// The chords that start with the pending keys and the keys that are left of each,
+    /// e.g. for a popup that lists what can be pressed next.
+    pub fn continuations(&self) -> impl Iterator<Item = (&[KeyBinding], &A)> {
+        let pending = self.pending();
+        self.chords.iter().filter_map(move |(keys, action)| {
+            let rest = keys.strip_prefix(pending.as_slice())?;
+            (!rest.is_empty()).then_some((rest, action))
+        })
+    }
+    /// How long until the pending keys time out, `None` if nothing is pending.
+    pub fn timeout(&self, now: Instant) -> Option<Duration> {
+        self.pending_since
+            .map(|since| self.chord_timeout.saturating_sub(now.duration_since(since)))
+    }
+}
+
+impl<A: Clone> Chords<A> {
+    pub fn feed(&mut self, event: Event, state: KeyState) {
+        self.feed_at(event, state, Instant::now());
+    }
+    pub fn feed_at(&mut self, event: Event, state: KeyState, now: Instant) {
+        let key = KeyBinding::from_event(&event, state);
+        if key.is_none() && self.pending.is_empty() {
+            self.events.push_back(ChordEvent::Unmatched(event, state));
+            return;
+        }
+        self.pending.push((event, state, key));
+        if key.is_none() {
+            return;
+        }
+
+        let pending = self.pending();
+        let longer = self
+            .chords
+            .iter()
+            .any(|(keys, _)| keys.len() > pending.len() && keys.starts_with(&pending));
+        if longer {
+            self.pending_since = Some(now);
+            return;
+        }
+        //Either a complete chord or the last key broke it.
+        self.resolve(now);
+    }
+    /// The next action or unmatched event. Pending keys are resolved if they timed out.
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<ChordEvent<A>> {
+        self.next_at(Instant::now())
+    }
+    pub fn next_at(&mut self, now: Instant) -> Option<ChordEvent<A>> {
+        if self.events.is_empty() && self.timeout(now) == Some(Duration::ZERO) {
+            self.flush();
+        }
+        self.events.pop_front()
+    }
+    /// Stop waiting for the rest of a chord. The pending keys become the chords they
+    /// start with, or unmatched events where they aren't one.
+    pub fn flush(&mut self) {
+        let now = Instant::now();
+        while !self.pending.is_empty() {
+            self.resolve(now);
+        }
+        self.pending_since = None;
+    }
+    /// Turn the longest chord the pending keys start with into its action, or the first key
+    /// into an unmatched event if there is none. What comes after is fed in again.
+    fn resolve(&mut self, now: Instant) {
+        let pending = std::mem::take(&mut self.pending);
+        let keys: Vec<_> = pending.iter().filter_map(|(_, _, key)| *key).collect();
+        let (len, action) = (1..=keys.len())
+            .rev()
+            .find_map(|len| Some((len, self.action(&keys[..len])?)))
+            .map_or((1, None), |(len, action)| (len, Some(action)));
+
+        //Up to and including the last key of the chord.
+        let mut seen = 0;
+        let end = pending
+            .iter()
+            .position(|(_, _, key)| {
+                seen += key.is_some() as usize;
+                seen == len
+            })
+            .map_or(pending.len(), |i| i + 1);
+
+        let mut rest = pending.into_iter();
+        let resolved = rest.by_ref().take(end);
+        match action {
+            Some(action) => {
+                self.events.push_back(ChordEvent::Action(action));
+                self.events.extend(
+                    resolved
+                        .filter(|(_, _, key)| key.is_none())
+                        .map(|(event, state, _)| ChordEvent::Unmatched(event, state)),
+                );
+            }
+            None => self
+                .events
+                .extend(resolved.map(|(event, state, _)| ChordEvent::Unmatched(event, state))),
+        }
+
+        self.pending_since = None;
+        for (event, state, _) in rest {
+            self.feed_at(event, state, now);
+        }
+    }
+    fn action(&self, keys: &[KeyBinding]) -> Option<A> {
+        self.chords
+            .iter()
+            .find(|(chord, _)| chord == keys)
+            .map(|(_, action)| action.clone())
+    }
+}
@@ -1,7 +1,5 @@
-use std::{
-    cmp::{max, min},
-    ptr::addr_of_mut,
-};
+use crate::error::WinterError;
+use std::cmp::{max, min};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Corner {
@@ -32,8 +30,6 @@ pub enum Alignment {
     Right,
 }
 
-static mut RECTS: Vec<Rect> = Vec::new();
-
 #[inline(always)]
 pub fn layout(area: Rect, direction: Direction, cons: &'_ [Constraint]) -> Vec<Rect> {
     layout_margin(area, direction, cons, (0, 0)).unwrap()
@@ -45,103 +41,105 @@ pub fn layout_margin(
     direction: Direction,
     cons: &'_ [Constraint],
     margin: (u16, u16),
-) -> Result<Vec<Rect>, &'static str> {
-    unsafe {
-        RECTS.clear();
-        let area = area.inner(margin.0, margin.1)?;
-        let mut x = area.x;
-        let mut y = area.y;
+) -> Result<Vec<Rect>, WinterError> {
+    let mut rects = Vec::new();
+    let area = area.inner(margin.0, margin.1)?;
+    let mut x = area.x;
+    let mut y = area.y;
 
-        //Fill can only be used at the start or end.
-        //There cannot be more than one.
-        //TODO: Add to result.
-        let count = cons.iter().filter(|con| **con == Constraint::Fill).count();
+    //Fill can only be used at the start or end.
+    //There cannot be more than one.
+    //TODO: Add to result.
+    let count = cons.iter().filter(|con| **con == Constraint::Fill).count();
 
-        if count > 1 {
-            return Err("Cannot have multiple fill constraints.");
-        }
+    if count > 1 {
+        return Err(WinterError::InvalidLayout(
+            "Cannot have multiple fill constraints.",
+        ));
+    }
 
-        let start = cons.starts_with(&[Constraint::Fill]);
-        let end = cons.ends_with(&[Constraint::Fill]);
+    let start = cons.starts_with(&[Constraint::Fill]);
+    let end = cons.ends_with(&[Constraint::Fill]);
 
-        if !(start || end) && count == 1 {
-            return Err("Fill constraint must be at the beginning or end.");
-        }
-
-        for con in cons {
-            match con {
-                Constraint::Percentage(p) => match direction {
-                    Direction::Horizontal => {
-                        let width = (area.width as f32 * (*p as f32 / 100.0)).round() as u16;
-                        let width = if width + x >= area.right() {
-                            area.right().saturating_sub(x)
-                        } else {
-                            width
-                        };
-                        RECTS.push(Rect::new(x, y, width, area.height));
-                        x += width;
-                    }
-                    Direction::Vertical => {
-                        let height = (area.height as f32 * (*p as f32 / 100.0)).round() as u16;
-                        let height = if height + y >= area.bottom() {
-                            area.bottom().saturating_sub(y)
-                        } else {
-                            height
-                        };
-                        RECTS.push(Rect::new(x, y, area.width, height));
-                        y += height;
-                    }
-                },
-                Constraint::Length(l) => match direction {
-                    Direction::Horizontal => {
-                        let l = if x + l >= area.right() {
-                            area.right().saturating_sub(x)
-                        } else {
-                            *l
-                        };
-                        RECTS.push(Rect::new(x, y, l, area.height));
-                        x += l;
-                    }
-                    Direction::Vertical => {
-                        let l = if y + l >= area.bottom() {
-                            area.bottom().saturating_sub(y)
-                        } else {
-                            *l
-                        };
-                        RECTS.push(Rect::new(x, y, area.width, l));
-                        y += l;
-                    }
-                },
-                _ => {}
-            }
-        }
+    if !(start || end) && count == 1 {
+        return Err(WinterError::InvalidLayout(
+            "Fill constraint must be at the beginning or end.",
+        ));
+    }
 
-        if start {
-            let rem_y = area.height - y;
-            let rem_x = area.width - x;
-            match direction {
+    for con in cons {
+        match con {
+            Constraint::Percentage(p) => match direction {
                 Direction::Horizontal => {
-                    for RECT in &mut *addr_of_mut!(RECTS) {
-                        RECT.x += rem_x;
-                    }
+                    let width = (area.width as f32 * (*p as f32 / 100.0)).round() as u16;
+                    let width = if width + x >= area.right() {
+                        area.right().saturating_sub(x)
+                    } else {
+                        width
+                    };
+                    rects.push(Rect::new(x, y, width, area.height));
+                    x += width;
                 }
                 Direction::Vertical => {
-                    for RECT in &mut *addr_of_mut!(RECTS) {
-                        RECT.y += rem_y;
-                    }
+                    let height = (area.height as f32 * (*p as f32 / 100.0)).round() as u16;
+                    let height = if height + y >= area.bottom() {
+                        area.bottom().saturating_sub(y)
+                    } else {
+                        height
+                    };
+                    rects.push(Rect::new(x, y, area.width, height));
+                    y += height;
                 }
-            }
-
-            RECTS.insert(0, Rect::new(0, 0, rem_x, rem_y));
+            },
+            Constraint::Length(l) => match direction {
+                Direction::Horizontal => {
+                    let l = if x + l >= area.right() {
+                        area.right().saturating_sub(x)
+                    } else {
+                        *l
+                    };
+                    rects.push(Rect::new(x, y, l, area.height));
+                    x += l;
+                }
+                Direction::Vertical => {
+                    let l = if y + l >= area.bottom() {
+                        area.bottom().saturating_sub(y)
+                    } else {
+                        *l
+                    };
+                    rects.push(Rect::new(x, y, area.width, l));
+                    y += l;
+                }
+            },
+            _ => {}
         }
+    }
 
-        if end {
-            //TODO: Overflow?
-            RECTS.push(Rect::new(x, y, area.width - x, area.height - y))
+    if start {
+        let rem_y = area.height - y;
+        let rem_x = area.width - x;
+        match direction {
+            Direction::Horizontal => {
+                for rect in &mut rects {
+                    rect.x += rem_x;
+                }
+            }
+            Direction::Vertical => {
+                for rect in &mut rects {
+                    rect.y += rem_y;
+                }
+            }
         }
 
-        Ok(std::mem::take(&mut *addr_of_mut!(RECTS)))
+        rects.insert(0, Rect::new(0, 0, rem_x, rem_y));
+    }
+
+    if end {
+        //TODO: Overflow?
+        rects.push(Rect::new(x, y, area.width - x, area.height - y))
     }
+
+    Ok(rects)
 }
 
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Default)]
@@ -156,7 +154,7 @@ impl Rect {
     /// Creates a new rect, with width and height limited to keep the area under max u16.
     /// If clipped, aspect ratio will be preserved.
     pub fn new(x: u16, y: u16, width: u16, height: u16) -> Rect {
-        let max_area = u16::max_value();
+        let max_area = u16::MAX;
         let (clipped_width, clipped_height) = if width as u32 * height as u32 > max_area as u32 {
             let aspect_ratio = width as f64 / height as f64;
             let max_area_f = max_area as f64;
@@ -174,23 +172,26 @@ impl Rect {
         }
     }
 
-    pub const fn centered(&self, width: u16, height: u16) -> Result<Rect, &'static str> {
+    pub const fn centered(&self, width: u16, height: u16) -> Result<Rect, WinterError> {
         let v = self.height / 2;
         let h = self.width / 2;
-        match self.inner(h.saturating_sub(width / 2), v.saturating_sub(height / 2)) {
-            Ok(mut rect) => {
-                rect.width = width;
-                rect.height = height;
+        let rect = Rect {
+            x: self.x + h.saturating_sub(width / 2),
+            y: self.y + v.saturating_sub(height / 2),
+            width,
+            height,
+        };
 
-                if rect.bottom() > self.bottom() {
-                    Err("Centered rectangle bounds exceed it's parent. Reduce the height.")
-                } else if rect.right() > self.right() {
-                    Err("Centered rectangle bounds exceed it's parent. Reduce the width.")
-                } else {
-                    Ok(rect)
-                }
-            }
-            Err(err) => Err(err),
+        if rect.bottom() > self.bottom() {
+            Err(WinterError::InvalidLayout(
+                "Centered rectangle bounds exceed it's parent. Reduce the height.",
+            ))
+        } else if rect.right() > self.right() {
+            Err(WinterError::InvalidLayout(
+                "Centered rectangle bounds exceed it's parent. Reduce the width.",
+            ))
+        } else {
+            Ok(rect)
         }
     }
 
@@ -214,11 +215,15 @@ impl Rect {
         self.y.saturating_add(self.height)
     }
 
-    pub const fn inner(self, w: u16, h: u16) -> Result<Rect, &'static str> {
+    pub const fn inner(self, w: u16, h: u16) -> Result<Rect, WinterError> {
         if self.width < 2 * w {
-            Err("Inner area exceeded outside area. Reduce margin width.")
+            Err(WinterError::InvalidLayout(
+                "Inner area exceeded outside area. Reduce margin width.",
+            ))
         } else if self.height < 2 * h {
-            Err("Inner area exceeded outside area. Reduce margin height.")
+            Err(WinterError::InvalidLayout(
+                "Inner area exceeded outside area. Reduce margin height.",
+            ))
         } else {
             Ok(Rect {
                 x: self.x + w,
@@ -261,4 +266,7 @@ impl Rect {
             && self.y < other.y + other.height
             && self.y + self.height > other.y
     }
+    pub const fn contains(self, x: u16, y: u16) -> bool {
+        x >= self.x && x < self.right() && y >= self.y && y < self.bottom()
+    }
 }
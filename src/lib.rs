@@ -1,14 +1,23 @@
-#![feature(macro_metavar_expr, const_fn_floating_point_arithmetic)]
 #![allow(non_camel_case_types, non_snake_case)]
 use std::{
     fmt::Display,
-    io::{stdin, stdout, Stdin, Stdout, Write},
-    mem::zeroed,
-    os::windows::io::AsRawHandle,
+    io::{Stdin, Stdout, Write},
     process::Command,
+    sync::atomic::{AtomicBool, Ordering},
     time::{Duration, Instant},
 };
 
+#[cfg(windows)]
+use std::{mem::zeroed, os::windows::io::AsRawHandle};
+
+#[cfg(unix)]
+use std::sync::{atomic::AtomicI32, Mutex};
+
+pub use app::{runner, App, Runner};
+pub use backend::{Backend, Console, TestBackend, WriterBackend};
+pub use error::WinterError;
+pub use input::InputParser;
+
 //Widgets
 pub use block::*;
 pub use block::{block, Block, BorderType::*, ALL, BOTTOM, LEFT, RIGHT, TOP};
@@ -22,18 +31,31 @@ pub mod guage;
 pub mod list;
 pub mod table;
 pub mod text;
+#[cfg(unix)]
+pub mod unix;
+#[cfg(windows)]
 pub mod win32;
 
-pub use buffer::{Buffer, Cell};
+pub use buffer::{Buffer, Cell, RenderStats};
 pub use layout::Alignment::*;
 pub use style::{Color::*, *};
+#[cfg(unix)]
+pub use unix::{AsRawHandle, RawHandle};
+#[cfg(windows)]
 pub use win32::*;
 
 pub use layout::Constraint::*;
 pub use layout::Direction::*;
 pub use layout::*;
 
+pub mod app;
+pub mod backend;
+pub mod base64;
 pub mod buffer;
+pub mod error;
+pub mod events;
+pub mod input;
+pub mod keymap;
 pub mod layout;
 pub mod style;
 pub mod symbols;
@@ -41,55 +63,610 @@ pub mod symbols;
 //Re-export unicode width.
 pub use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
-pub struct Winter {
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Viewport {
+    #[default]
+    Fullscreen,
+    /// A fixed number of rows below the cursor. The shell stays visible above
+    /// and the last frame is left in the scrollback on exit.
+    Inline(u16),
+}
+
+/// DECSCUSR cursor styles, `Default` is whatever the user configured.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CursorShape {
+    #[default]
+    Default,
+    BlinkingBlock,
+    SteadyBlock,
+    BlinkingUnderline,
+    SteadyUnderline,
+    BlinkingBar,
+    SteadyBar,
+}
+
+/// How `Winter::notify` sends desktop notifications, terminals support one or the other.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Notification {
+    /// iTerm2, kitty, WezTerm, Windows Terminal and others.
+    #[default]
+    Osc9,
+    /// urxvt, foot and VTE based terminals like GNOME Terminal.
+    Osc777,
+}
+
+impl Notification {
+    /// Guess from the environment, OSC 9 unless the terminal is known to only support OSC 777.
+    pub fn detect() -> Self {
+        let term = std::env::var("TERM").unwrap_or_default();
+        if term.starts_with("rxvt")
+            || term.starts_with("foot")
+            || std::env::var_os("VTE_VERSION").is_some()
+        {
+            Notification::Osc777
+        } else {
+            Notification::Osc9
+        }
+    }
+}
+
+/// Which terminal modes `Winter` turns on, everything is enabled by default.
+///
+/// ```no_run
+/// use winter::*;
+///
+/// //Keep the shell's scrollback and let the user select text.
+/// let winter = Winter::with_options(
+///     WinterOptions::new()
+///         .alternate_screen(false)
+///         .mouse_capture(false),
+/// )?;
+/// # Ok::<(), WinterError>(())
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WinterOptions {
+    pub alternate_screen: bool,
+    pub raw_mode: bool,
+    pub mouse_capture: bool,
+    /// Report pasted text as one `Event::Paste` instead of a key press per character.
+    pub bracketed_paste: bool,
+    /// Send `Event::FocusGained` and `Event::FocusLost` when the window gains or loses focus.
+    pub focus_reporting: bool,
+    /// Report `KeyKind::Release` events as well, off by default.
+    pub key_release: bool,
+    /// Ask terminals that speak the kitty keyboard protocol for unambiguous key events,
+    /// e.g. Ctrl+I apart from Tab. Others ignore it.
+    pub kitty_keyboard: bool,
+    pub hide_cursor: bool,
+    pub clear_on_start: bool,
+    pub viewport: Viewport,
+    /// Stop the process on Ctrl+Z like a shell program would. Unix only.
+    pub suspend_on_ctrl_z: bool,
+    /// Wrap each frame in synchronized update sequences (mode 2026) so it's shown all at once.
+    /// `None` asks the terminal on start, which needs raw mode.
+    pub synchronized_output: Option<bool>,
+}
+
+impl WinterOptions {
+    pub const fn new() -> Self {
+        Self {
+            alternate_screen: true,
+            raw_mode: true,
+            mouse_capture: true,
+            bracketed_paste: true,
+            focus_reporting: true,
+            key_release: false,
+            kitty_keyboard: true,
+            hide_cursor: true,
+            clear_on_start: true,
+            viewport: Viewport::Fullscreen,
+            suspend_on_ctrl_z: true,
+            synchronized_output: None,
+        }
+    }
+    pub fn alternate_screen(mut self, alternate_screen: bool) -> Self {
+        self.alternate_screen = alternate_screen;
+        self
+    }
+    pub fn raw_mode(mut self, raw_mode: bool) -> Self {
+        self.raw_mode = raw_mode;
+        self
+    }
+    pub fn mouse_capture(mut self, mouse_capture: bool) -> Self {
+        self.mouse_capture = mouse_capture;
+        self
+    }
+    pub fn bracketed_paste(mut self, bracketed_paste: bool) -> Self {
+        self.bracketed_paste = bracketed_paste;
+        self
+    }
+    pub fn focus_reporting(mut self, focus_reporting: bool) -> Self {
+        self.focus_reporting = focus_reporting;
+        self
+    }
+    pub fn key_release(mut self, key_release: bool) -> Self {
+        self.key_release = key_release;
+        self
+    }
+    pub fn kitty_keyboard(mut self, kitty_keyboard: bool) -> Self {
+        self.kitty_keyboard = kitty_keyboard;
+        self
+    }
+    pub fn hide_cursor(mut self, hide_cursor: bool) -> Self {
+        self.hide_cursor = hide_cursor;
+        self
+    }
+    pub fn clear_on_start(mut self, clear_on_start: bool) -> Self {
+        self.clear_on_start = clear_on_start;
+        self
+    }
+    pub fn suspend_on_ctrl_z(mut self, suspend_on_ctrl_z: bool) -> Self {
+        self.suspend_on_ctrl_z = suspend_on_ctrl_z;
+        self
+    }
+    /// Force synchronized output on or off instead of asking the terminal.
+    pub fn synchronized_output(mut self, synchronized_output: bool) -> Self {
+        self.synchronized_output = Some(synchronized_output);
+        self
+    }
+    /// Draw in `height` rows below the cursor instead of taking over the screen.
+    /// The alternate screen is not used in this mode.
+    pub fn inline(mut self, height: u16) -> Self {
+        self.viewport = Viewport::Inline(height);
+        self
+    }
+}
+
+impl Default for WinterOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub struct Winter<B: Backend = Console> {
     pub viewport: Rect,
     pub buffers: [Buffer; 2],
     pub current: usize,
-    pub stdout: Stdout,
-    pub stdin: Stdin,
+    pub backend: B,
+    /// The modes that are currently enabled.
+    pub options: WinterOptions,
+    /// The modes `resume` turns back on, `None` unless suspended.
+    pub suspended: Option<WinterOptions>,
+    /// What the last `draw` cost.
+    pub stats: RenderStats,
+    /// Where `draw` leaves a visible cursor, in the same coordinates as `buffer`.
+    pub cursor: Option<(u16, u16)>,
+    pub cursor_shape: CursorShape,
+    //The cursor is visible on screen because of `cursor`.
+    cursor_shown: bool,
+    /// Set with `set_title`, the old title is restored when the terminal is given back.
+    pub title: Option<String>,
+    pub notification: Notification,
+    /// When the visual bell from `flash` ends, the first `draw` after that clears it.
+    pub flash_until: Option<Instant>,
 }
 
 impl Winter {
-    //TODO: WinterOpitons or something?
-    //Alternate screen, raw mode, mouse support.
-    //I want to be able to use copy and paste sometimes.
-    pub fn new() -> Self {
-        let mut stdout = stdout();
-        let stdin = stdin();
-        //Enable ANSI codes on conhost terminals, can also use:
-        //[HKEY_CURRENT_USER\Console]
-        //"VirtualTerminalLevel"=dword:00000001
-        //https://ss64.com/nt/syntax-ansi.html
-        set_mode(
-            stdout.as_raw_handle(),
-            ENABLE_PROCESSED_OUTPUT | ENABLE_VIRTUAL_TERMINAL_PROCESSING,
-        );
-        set_mode(
-            stdin.as_raw_handle(),
-            ENABLE_WINDOW_INPUT | ENABLE_EXTENDED_FLAGS | ENABLE_MOUSE_INPUT,
-        );
-
-        show_alternate_screen(&mut stdout);
-        clear(&mut stdout);
-
-        let raw = stdout.as_raw_handle();
-        let (width, height) = info(raw).window_size;
-        let viewport = Rect::new(0, 0, width, height);
+    pub fn new() -> Result<Self, WinterError> {
+        Self::with_options(WinterOptions::new())
+    }
+    pub fn with_options(options: WinterOptions) -> Result<Self, WinterError> {
+        Self::with_backend_options(Console::new(), options)
+    }
+}
 
-        Self {
-            viewport,
-            buffers: [Buffer::empty(viewport), Buffer::empty(viewport)],
+impl<B: Backend> Winter<B> {
+    pub fn with_backend(backend: B) -> Result<Self, WinterError> {
+        Self::with_backend_options(backend, WinterOptions::new())
+    }
+    pub fn with_backend_options(
+        mut backend: B,
+        options: WinterOptions,
+    ) -> Result<Self, WinterError> {
+        backend.init()?;
+
+        //Modes are turned on one at a time, if one fails `Drop` restores the ones before it.
+        let mut winter = Self {
+            viewport: Rect::default(),
+            buffers: [
+                Buffer::empty(Rect::default()),
+                Buffer::empty(Rect::default()),
+            ],
             current: 0,
-            stdout,
-            stdin,
+            backend,
+            options: WinterOptions {
+                alternate_screen: false,
+                raw_mode: false,
+                mouse_capture: false,
+                bracketed_paste: false,
+                focus_reporting: false,
+                kitty_keyboard: false,
+                ..options
+            },
+            suspended: None,
+            stats: RenderStats::default(),
+            cursor: None,
+            cursor_shape: CursorShape::Default,
+            cursor_shown: false,
+            title: None,
+            notification: Notification::detect(),
+            flash_until: None,
+        };
+        winter.enter(options, options.clear_on_start)?;
+        Ok(winter)
+    }
+    /// Give the terminal back, e.g. to run `$EDITOR`. Restores the console modes,
+    /// leaves the alternate screen and shows the cursor.
+    ///
+    /// ```no_run
+    /// use winter::*;
+    ///
+    /// let mut winter = Winter::new()?;
+    /// winter.suspend()?;
+    /// std::process::Command::new("vim").status()?;
+    /// winter.resume()?;
+    /// # Ok::<(), WinterError>(())
+    /// ```
+    pub fn suspend(&mut self) -> Result<(), WinterError> {
+        if self.suspended.is_none() {
+            self.suspended = Some(self.options);
+            self.leave()?;
         }
+        Ok(())
     }
-    pub fn draw(&mut self) {
+    /// Turn the modes from before `suspend` back on. The next `draw` repaints everything.
+    pub fn resume(&mut self) -> Result<(), WinterError> {
+        if let Some(options) = self.suspended.take() {
+            self.enter(options, true)?;
+        }
+        Ok(())
+    }
+    /// Turn on the modes in `options` and work out the viewport again.
+    fn enter(&mut self, options: WinterOptions, clear: bool) -> Result<(), WinterError> {
+        self.backend.set_job_control(true)?;
+        if options.raw_mode {
+            self.backend.set_raw_mode(true)?;
+            self.options.raw_mode = true;
+        }
+        if options.mouse_capture {
+            self.backend.set_mouse_capture(true)?;
+            self.options.mouse_capture = true;
+        }
+        if options.bracketed_paste {
+            self.backend.set_bracketed_paste(true)?;
+            self.options.bracketed_paste = true;
+        }
+        if options.focus_reporting {
+            self.backend.set_focus_reporting(true)?;
+            self.options.focus_reporting = true;
+        }
+        //Only asked once, `resume` gets the answer through `options`.
+        if options.synchronized_output.is_none() {
+            let supported = self.options.raw_mode && self.backend.synchronized_output()?;
+            self.options.synchronized_output = Some(supported);
+        }
+        if options.hide_cursor {
+            self.backend.hide_cursor();
+        }
+        if self.cursor_shape != CursorShape::Default {
+            set_cursor_shape(&mut self.backend, self.cursor_shape);
+        }
+        self.cursor_shown = false;
+        if let Some(title) = &self.title {
+            push_title(&mut self.backend);
+            set_title(&mut self.backend, title);
+        }
+
+        let backend = &mut self.backend;
+        self.viewport = match options.viewport {
+            Viewport::Fullscreen => {
+                if options.alternate_screen {
+                    backend.set_alternate_screen(true)?;
+                    self.options.alternate_screen = true;
+                }
+                if clear {
+                    backend.clear();
+                }
+                let (width, height) = backend.size()?;
+                Rect::new(0, 0, width, height)
+            }
+            Viewport::Inline(height) => {
+                let (width, max_height) = backend.size()?;
+                let height = height.min(max_height);
+
+                //Make room below the cursor, the terminal scrolls up if it's near the bottom.
+                write!(backend, "\r")?;
+                for _ in 1..height {
+                    backend.line_feed();
+                }
+                backend.flush()?;
+
+                //The cursor is on the last row of the viewport now. The reply to the
+                //position query is line buffered outside raw mode, so it's needed here.
+                if !self.options.raw_mode {
+                    backend.set_raw_mode(true)?;
+                }
+                let position = backend.cursor_position();
+                if !self.options.raw_mode {
+                    backend.set_raw_mode(false)?;
+                }
+                let (_, bottom) = position?;
+                let y = bottom.saturating_sub(height.saturating_sub(1));
+                if clear {
+                    backend.move_to(1, y + 1);
+                    backend.clear_from_cursor();
+                }
+                Rect::new(0, y, width, height)
+            }
+        };
+
+        //Each screen has its own stack of flags, push them on the one we're drawing to.
+        if options.kitty_keyboard {
+            self.backend.set_kitty_keyboard(true)?;
+            self.options.kitty_keyboard = true;
+        }
+
+        self.buffers[self.current].resize(self.viewport);
+        self.buffers[1 - self.current].resize(self.viewport);
+        //Reset the back buffer to make sure the next update will redraw everything.
+        self.buffers[1 - self.current].reset();
+        self.backend.flush()?;
+        Ok(())
+    }
+    /// Turn off every enabled mode, the terminal is left the way it was found.
+    fn leave(&mut self) -> Result<(), WinterError> {
+        if let Viewport::Inline(_) = self.options.viewport {
+            //Keep the last frame and put the prompt below it.
+            if self.viewport.height != 0 {
+                self.backend.move_to(1, self.viewport.bottom());
+                self.backend.line_feed();
+            }
+        }
+        if self.options.mouse_capture {
+            self.backend.set_mouse_capture(false)?;
+            self.options.mouse_capture = false;
+        }
+        if self.options.bracketed_paste {
+            self.backend.set_bracketed_paste(false)?;
+            self.options.bracketed_paste = false;
+        }
+        if self.options.focus_reporting {
+            self.backend.set_focus_reporting(false)?;
+            self.options.focus_reporting = false;
+        }
+        //Before leaving the alternate screen, the flags were pushed there.
+        if self.options.kitty_keyboard {
+            self.backend.set_kitty_keyboard(false)?;
+            self.options.kitty_keyboard = false;
+        }
+        if self.options.alternate_screen {
+            self.backend.set_alternate_screen(false)?;
+            self.options.alternate_screen = false;
+        }
+        self.backend.show_cursor();
+        if self.cursor_shape != CursorShape::Default {
+            set_cursor_shape(&mut self.backend, CursorShape::Default);
+        }
+        if self.title.is_some() {
+            pop_title(&mut self.backend);
+        }
+        reset(&mut self.backend);
+        if self.options.raw_mode {
+            self.backend.set_raw_mode(false)?;
+            self.options.raw_mode = false;
+        }
+        self.backend.set_job_control(false)?;
+        self.backend.flush()?;
+        Ok(())
+    }
+    pub fn set_raw_mode(&mut self, enabled: bool) -> Result<(), WinterError> {
+        if self.options.raw_mode != enabled {
+            self.backend.set_raw_mode(enabled)?;
+            self.options.raw_mode = enabled;
+        }
+        Ok(())
+    }
+    /// Turn this off to let the user select text with the terminal's own selection.
+    pub fn set_mouse_capture(&mut self, enabled: bool) -> Result<(), WinterError> {
+        if self.options.mouse_capture != enabled {
+            self.backend.set_mouse_capture(enabled)?;
+            self.backend.flush()?;
+            self.options.mouse_capture = enabled;
+        }
+        Ok(())
+    }
+    pub fn set_bracketed_paste(&mut self, enabled: bool) -> Result<(), WinterError> {
+        if self.options.bracketed_paste != enabled {
+            self.backend.set_bracketed_paste(enabled)?;
+            self.backend.flush()?;
+            self.options.bracketed_paste = enabled;
+        }
+        Ok(())
+    }
+    pub fn set_focus_reporting(&mut self, enabled: bool) -> Result<(), WinterError> {
+        if self.options.focus_reporting != enabled {
+            self.backend.set_focus_reporting(enabled)?;
+            self.backend.flush()?;
+            self.options.focus_reporting = enabled;
+        }
+        Ok(())
+    }
+    pub fn set_kitty_keyboard(&mut self, enabled: bool) -> Result<(), WinterError> {
+        if self.options.kitty_keyboard != enabled {
+            self.backend.set_kitty_keyboard(enabled)?;
+            self.backend.flush()?;
+            self.options.kitty_keyboard = enabled;
+        }
+        Ok(())
+    }
+    /// The other screen has different content, so the next `draw` repaints everything.
+    /// Does nothing with an inline viewport.
+    pub fn set_alternate_screen(&mut self, enabled: bool) -> Result<(), WinterError> {
+        if self.options.alternate_screen != enabled && self.options.viewport == Viewport::Fullscreen
+        {
+            //Each screen has its own stack of flags, move them to the new one.
+            let kitty_keyboard = self.options.kitty_keyboard;
+            self.set_kitty_keyboard(false)?;
+            self.backend.set_alternate_screen(enabled)?;
+            self.set_kitty_keyboard(kitty_keyboard)?;
+            if self.options.hide_cursor {
+                self.backend.hide_cursor();
+            }
+            self.backend.clear();
+            self.buffers[1 - self.current].reset();
+            self.options.alternate_screen = enabled;
+        }
+        Ok(())
+    }
+    /// Draw the changes since the last frame and flush them, see `stats` for what it cost.
+    pub fn draw(&mut self) -> Result<(), WinterError> {
+        //The terminal holds the frame until the end, including the clear after a resize.
+        let synchronized = self.options.synchronized_output == Some(true);
+        if synchronized {
+            begin_synchronized_update(&mut self.backend);
+        }
+        //Otherwise the cursor is seen jumping around while the cells are drawn.
+        let was_shown = std::mem::take(&mut self.cursor_shown);
+        if was_shown {
+            self.backend.hide_cursor();
+        }
+        let result = self.draw_frame();
+        match self.cursor.filter(|&(x, y)| self.viewport.contains(x, y)) {
+            Some((x, y)) => {
+                self.backend.move_to(x + 1, y + 1);
+                self.backend.show_cursor();
+                self.cursor_shown = true;
+            }
+            None if was_shown && !self.options.hide_cursor => self.backend.show_cursor(),
+            None => {}
+        }
+        if synchronized {
+            end_synchronized_update(&mut self.backend);
+        }
+        result?;
+
+        self.stats.bytes = self.backend.buffered_len();
+        let start = Instant::now();
+        self.backend.flush()?;
+        self.stats.write_time += start.elapsed();
+        Ok(())
+    }
+    /// Show the cursor at `(x, y)` after each `draw`, e.g. for a text input. `None` hides it again.
+    ///
+    /// ```no_run
+    /// use winter::*;
+    ///
+    /// let mut winter = Winter::new()?;
+    /// let text = "Search: ";
+    /// lines!(text).draw(winter.viewport, winter.buffer());
+    /// winter.set_cursor(Some((text.len() as u16, 0)));
+    /// winter.set_cursor_shape(CursorShape::BlinkingBar);
+    /// winter.draw()?;
+    /// # Ok::<(), WinterError>(())
+    /// ```
+    pub fn set_cursor(&mut self, cursor: Option<(u16, u16)>) {
+        self.cursor = cursor;
+    }
+    /// Takes effect immediately and is reset when the terminal is given back.
+    pub fn set_cursor_shape(&mut self, shape: CursorShape) {
+        if self.cursor_shape != shape {
+            set_cursor_shape(&mut self.backend, shape);
+            self.cursor_shape = shape;
+        }
+    }
+    /// Copy `text` with OSC 52, the terminal has to allow it.
+    /// Useful with mouse capture on, which turns off the terminal's own selection.
+    pub fn copy_to_clipboard(&mut self, text: &str) -> Result<(), WinterError> {
+        copy_to_clipboard(&mut self.backend, text);
+        Ok(self.backend.flush()?)
+    }
+    /// Ask the terminal for the clipboard, it arrives later as `Event::Clipboard`.
+    /// Many terminals ignore this or ask the user first. Windows needs `virtual_terminal_input`.
+    pub fn request_clipboard(&mut self) -> Result<(), WinterError> {
+        request_clipboard(&mut self.backend);
+        Ok(self.backend.flush()?)
+    }
+    /// Set the window or tab title. The title from before is saved on the terminal's
+    /// title stack the first time and restored on exit.
+    pub fn set_title(&mut self, title: &str) -> Result<(), WinterError> {
+        if self.title.is_none() {
+            push_title(&mut self.backend);
+        }
+        set_title(&mut self.backend, title);
+        self.title = Some(title.to_string());
+        Ok(self.backend.flush()?)
+    }
+    /// Put back the title from before the first `set_title`.
+    pub fn reset_title(&mut self) -> Result<(), WinterError> {
+        if self.title.take().is_some() {
+            pop_title(&mut self.backend);
+        }
+        Ok(self.backend.flush()?)
+    }
+    pub fn bell(&mut self) -> Result<(), WinterError> {
+        bell(&mut self.backend);
+        Ok(self.backend.flush()?)
+    }
+    /// Visual bell, shows the last frame inverted until `duration` has passed.
+    /// Frames drawn before then are inverted too, draw again after `flash_until` to clear it.
+    /// Anything already drawn to `buffer` for the next frame is kept.
+    pub fn flash(&mut self, duration: Duration) -> Result<(), WinterError> {
+        self.flash_until = Some(Instant::now() + duration);
+        let screen = self.buffers[1 - self.current].clone();
+        let next = std::mem::replace(&mut self.buffers[self.current], screen);
+        self.draw()?;
+        self.buffers[self.current] = next;
+        Ok(())
+    }
+    /// Desktop notification, see `Notification` for which terminals show them.
+    ///
+    /// ```no_run
+    /// use winter::*;
+    ///
+    /// let mut winter = Winter::new()?;
+    /// winter.set_title("▶ Song – Artist")?;
+    /// winter.notify("Now playing", "Song – Artist")?;
+    /// # Ok::<(), WinterError>(())
+    /// ```
+    pub fn notify(&mut self, title: &str, body: &str) -> Result<(), WinterError> {
+        notify(&mut self.backend, self.notification, title, body);
+        Ok(self.backend.flush()?)
+    }
+    fn draw_frame(&mut self) -> Result<(), WinterError> {
+        match self.flash_until {
+            Some(until) if Instant::now() < until => {
+                for cell in &mut self.buffers[self.current].content {
+                    cell.modifier.toggle(Modifier::INVERT);
+                }
+            }
+            _ => self.flash_until = None,
+        }
+
         //Calculate difference and draw to the terminal.
-        let previous_buffer = &self.buffers[1 - self.current];
-        let current_buffer = &self.buffers[self.current];
+        let start = Instant::now();
+        let [first, second] = &mut self.buffers;
+        let (previous_buffer, current_buffer) = match self.current {
+            0 => (second, &*first),
+            _ => (first, &*second),
+        };
+        //Shift what's on screen first so only the uncovered rows are in the diff.
+        let scroll = previous_buffer.find_scroll(current_buffer);
+        if let Some(scroll) = scroll {
+            previous_buffer.scroll(scroll);
+        }
         let diff = previous_buffer.diff(current_buffer);
-        buffer::draw(&mut self.stdout, diff);
+        let diff_time = start.elapsed();
+
+        let start = Instant::now();
+        if let Some(scroll) = scroll {
+            self.backend.scroll(scroll);
+        }
+        self.stats = RenderStats {
+            scroll,
+            diff_time,
+            ..self.backend.draw(diff)
+        };
+        self.stats.write_time = start.elapsed();
 
         //Swap buffers
         self.buffers[1 - self.current].reset();
@@ -97,8 +674,14 @@ impl Winter {
 
         //Update the viewport area.
         //TODO: I think there is a resize event that might be better.
-        let (width, height) = info(self.stdout.as_raw_handle()).window_size;
-        self.viewport = Rect::new(0, 0, width, height);
+        let (width, height) = self.backend.size()?;
+        self.viewport = match self.options.viewport {
+            Viewport::Fullscreen => Rect::new(0, 0, width, height),
+            Viewport::Inline(rows) => {
+                let rows = rows.min(height);
+                Rect::new(0, self.viewport.y.min(height - rows), width, rows)
+            }
+        };
 
         //Resize
         if self.buffers[self.current].area != self.viewport {
@@ -108,39 +691,138 @@ impl Winter {
             //Reset the back buffer to make sure the next update will redraw everything.
             self.buffers[1 - self.current].reset();
             //Screen must be cleared here.
-            clear(&mut self.stdout);
+            self.clear_viewport();
+        }
+        Ok(())
+    }
+    fn clear_viewport(&mut self) {
+        match self.options.viewport {
+            Viewport::Fullscreen => self.backend.clear(),
+            Viewport::Inline(_) => {
+                self.backend.move_to(1, self.viewport.y + 1);
+                self.backend.clear_from_cursor();
+            }
         }
     }
+    /// Print `height` rows above an inline viewport, they scroll into the scrollback like normal output.
+    /// Does nothing in fullscreen mode.
+    ///
+    /// ```no_run
+    /// use winter::*;
+    ///
+    /// let mut winter = Winter::with_options(WinterOptions::new().inline(3))?;
+    /// winter.insert_before(1, |buf| {
+    ///     lines!("Downloaded ", "song.flac".bold()).draw(buf.area, buf);
+    /// })?;
+    /// # Ok::<(), WinterError>(())
+    /// ```
+    pub fn insert_before(
+        &mut self,
+        height: u16,
+        draw: impl FnOnce(&mut Buffer),
+    ) -> Result<(), WinterError> {
+        if self.options.viewport == Viewport::Fullscreen {
+            return Ok(());
+        }
 
-    pub fn poll(&self) -> Option<(Event, KeyState)> {
-        self.poll_timeout(Duration::from_secs(0))
+        let area = Rect::new(0, 0, self.viewport.width, height);
+        let mut lines = Buffer::empty(area);
+        draw(&mut lines);
+        let diff = Buffer::empty(area).diff(&lines);
+
+        let (_, max_height) = self.backend.size()?;
+        let mut y = self.viewport.y;
+
+        //The live region is redrawn below the new lines.
+        self.clear_viewport();
+
+        for row in 0..height {
+            //No room left below, scroll the screen up.
+            if y + self.viewport.height >= max_height && y > 0 {
+                self.backend.move_to(1, max_height);
+                self.backend.line_feed();
+                y -= 1;
+            }
+
+            let cells = diff
+                .iter()
+                .filter(|(_, cell_y, _)| *cell_y == row)
+                .map(|(x, _, cell)| (*x, y, *cell))
+                .collect();
+            self.backend.draw(cells);
+            y += 1;
+        }
+
+        self.viewport.y = y;
+        self.buffers[self.current].resize(self.viewport);
+        self.buffers[1 - self.current].resize(self.viewport);
+        self.buffers[1 - self.current].reset();
+        self.clear_viewport();
+        self.backend.flush()?;
+        Ok(())
     }
-    pub fn poll_timeout(&self, timeout: Duration) -> Option<(Event, KeyState)> {
-        let now = Instant::now();
-        let handle = self.stdin.as_raw_handle();
 
+    pub fn poll(&mut self) -> Result<Option<(Event, KeyState)>, WinterError> {
+        self.poll_timeout(Duration::from_secs(0))
+    }
+    /// Wait at most `timeout` for an event, `None` only once it passed.
+    pub fn poll_timeout(
+        &mut self,
+        timeout: Duration,
+    ) -> Result<Option<(Event, KeyState)>, WinterError> {
+        let deadline = Instant::now() + timeout;
         loop {
-            let leftover = timeout.saturating_sub(now.elapsed());
-            if event_ready(handle, Some(leftover)) && event_count(handle) != 0 {
-                let input_event = read_input_event(handle);
-                let event = unsafe { convert_event(input_event.clone()) };
-                //TODO: This could be done better.
-                return if let Some(event) = event {
-                    let state = key_state(input_event);
-                    Some((event, state))
-                } else {
-                    None
-                };
+            let leftover = deadline.saturating_duration_since(Instant::now());
+            let Some((event, state)) = self.backend.poll(leftover)? else {
+                return Ok(None);
+            };
+            //Filtered out by `handle`, keep waiting for the rest of the timeout.
+            if let Some(event) = self.handle(event, state)? {
+                return Ok(Some(event));
             }
-
-            //Timeout elapsed
-            if now.elapsed().as_millis() >= timeout.as_millis() {
-                return None;
+        }
+    }
+    /// React to events the terminal cares about, like Ctrl+Z, and drop key releases
+    /// unless `WinterOptions::key_release` is set.
+    /// `poll` already does this, use it for events read elsewhere, e.g. with `Events`.
+    pub fn handle(
+        &mut self,
+        event: Event,
+        state: KeyState,
+    ) -> Result<Option<(Event, KeyState)>, WinterError> {
+        match (event, state) {
+            (Event::Suspend, _) => self.stop(),
+            (Event::Key(key), _) if key.kind == KeyKind::Release && !self.options.key_release => {
+                Ok(None)
+            }
+            #[cfg(unix)]
+            (Event::Key(key), state)
+                if state.without_locks() == KeyState(CONTROL)
+                    && key.code == KeyCode::Char('z')
+                    && key.kind == KeyKind::Press
+                    && self.options.suspend_on_ctrl_z =>
+            {
+                self.stop()
             }
+            //Stopped by something else, the shell may have reset the terminal.
+            //Leave first so the flag and title stacks don't grow.
+            (Event::Resume, state) if self.suspended.is_none() => {
+                self.suspend()?;
+                self.resume()?;
+                Ok(Some((Event::Resume, state)))
+            }
+            event => Ok(Some(event)),
         }
     }
+    /// Suspend and stop the process, then resume once the shell continues it.
+    fn stop(&mut self) -> Result<Option<(Event, KeyState)>, WinterError> {
+        self.suspend()?;
+        self.backend.stop_process()?;
+        self.resume()?;
+        Ok(Some((Event::Resume, KeyState(0))))
+    }
     pub fn flush(&mut self) -> Result<(), std::io::Error> {
-        self.stdout.flush()
+        self.backend.flush()
     }
     pub fn buffer(&mut self) -> &mut Buffer {
         &mut self.buffers[self.current]
@@ -160,16 +842,37 @@ impl Winter {
 /// }));
 /// ```
 pub fn uninit(stdout: &mut Stdout, stdin: &mut Stdin) {
-    set_mode(stdin.as_raw_handle(), 0);
+    //Best effort, there is nothing left to report errors to.
+    #[cfg(windows)]
+    let _ = set_mode(stdin.as_raw_handle(), 0);
+
+    #[cfg(unix)]
+    {
+        disable_mouse_capture(stdout);
+        let _ = disable_raw_mode(stdin.as_raw_handle());
+        release_job_control();
+    }
+
+    disable_bracketed_paste(stdout);
+    disable_focus_reporting(stdout);
+    //Popping with nothing pushed would pop the shell's own flags.
+    if KEYBOARD_FLAGS_PUSHED.swap(false, Ordering::AcqRel) {
+        pop_keyboard_flags(stdout);
+    }
     hide_alternate_screen(stdout);
     show_cursor(stdout);
+    set_cursor_shape(stdout, CursorShape::Default);
     reset(stdout);
-    stdout.flush().unwrap();
+    let _ = stdout.flush();
 }
 
-impl Drop for Winter {
+impl<B: Backend> Drop for Winter<B> {
     fn drop(&mut self) {
-        uninit(&mut self.stdout, &mut self.stdin);
+        //Errors are ignored, there is nothing left to report them to.
+        if self.suspended.is_none() {
+            let _ = self.leave();
+        }
+        let _ = self.backend.uninit();
     }
 }
 
@@ -177,19 +880,23 @@ pub struct Info {
     pub buffer_size: (u16, u16),
     ///Use this one.
     pub window_size: (u16, u16),
+    ///Relative to the buffer, not the window.
     pub cursor_position: (u16, u16),
+    ///Top left corner of the window inside the buffer.
+    pub window_position: (u16, u16),
 }
 
+#[cfg(windows)]
 ///TODO: windows starts counting at 0, unix at 1, add one to replicated unix behaviour.
 ///I still haven't figured out why my drawing is different than crossterm.
-pub fn info(output: *mut c_void) -> Info {
+pub fn info(output: *mut c_void) -> Result<Info, WinterError> {
     unsafe {
         let mut info: CONSOLE_SCREEN_BUFFER_INFO = zeroed();
         let result = GetConsoleScreenBufferInfo(output, &mut info);
         if result != 1 {
-            panic!("Could not get window size. result: {}", result);
+            return Err(WinterError::last_os_error());
         }
-        Info {
+        Ok(Info {
             buffer_size: (info.dwSize.X as u16, info.dwSize.Y as u16),
             window_size: (
                 (info.srWindow.Right - info.srWindow.Left) as u16 + 1,
@@ -199,76 +906,212 @@ pub fn info(output: *mut c_void) -> Info {
                 info.dwCursorPosition.X as u16,
                 info.dwCursorPosition.Y as u16,
             ),
-        }
+            window_position: (info.srWindow.Left, info.srWindow.Top),
+        })
     }
 }
 
+#[cfg(windows)]
 /// This wraps
 /// [`SetConsoleMode`](https://learn.microsoft.com/en-us/windows/console/setconsolemode).
-pub fn set_mode(handle: *mut c_void, mode: u32) {
+pub fn set_mode(handle: *mut c_void, mode: u32) -> Result<(), WinterError> {
     unsafe {
         let result = SetConsoleMode(handle, mode);
         if result != 1 {
-            let os_error = std::io::Error::last_os_error();
-            panic!("Failed to set console mode to {}: {os_error:#}", mode);
+            return Err(WinterError::last_os_error());
         }
     }
+    Ok(())
 }
 
-pub fn get_mode(handle: *mut c_void) -> u32 {
+#[cfg(windows)]
+pub fn get_mode(handle: *mut c_void) -> Result<u32, WinterError> {
     unsafe {
         let mut mode: u32 = 0;
         if GetConsoleMode(handle, &mut mode) == 0 {
-            panic!("Failed to get console mode");
+            return Err(WinterError::last_os_error());
         }
-        mode
+        Ok(mode)
     }
 }
 
-// TODO: double clicks? They might be nice to have...or not.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Event {
-    //Mouse
-    LeftMouse(u16, u16),
-    RightMouse(u16, u16),
-    MiddleMouse(u16, u16),
-    ScrollUp,
-    ScrollDown,
+    Key(KeyEvent),
+    Mouse(MouseEvent),
+    Resize(u16, u16),
+    ///Ctrl+Z or SIGTSTP. `Winter::poll` handles this itself.
+    Suspend,
+    ///The process was continued after being stopped, everything should be redrawn.
+    Resume,
+    ///The terminal's reply to `Winter::request_clipboard`.
+    Clipboard(String),
+    ///Pasted text with line endings as `\n`, needs `WinterOptions::bracketed_paste`.
+    Paste(String),
+    ///The terminal window gained focus, needs `WinterOptions::focus_reporting`.
+    FocusGained,
+    FocusLost,
+}
+
+impl Display for Event {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Event::Key(key) => write!(f, "Event::Key({:?}, {:?})", key.code, key.kind),
+            _ => write!(f, "Event::{:?}", self),
+        }
+    }
+}
 
-    //Key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeyCode {
+    /// Text, uppercase letters come with `SHIFT`.
     Char(char),
     Function(u8),
     Enter,
     Backspace,
     Escape,
-    Control,
-    Shift,
-    Alt,
     Tab,
     Up,
     Down,
     Left,
     Right,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Insert,
+    Delete,
+    /// Modifier keys on their own, only the win32 console reports these.
+    Control,
+    Shift,
+    Alt,
+    Media(MediaKey),
+    /// A win32 virtual key code.
     Unknown(u16),
+}
 
-    //Other
-    Resize(u16, u16),
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MediaKey {
+    Play,
+    Pause,
+    PlayPause,
+    Stop,
+    FastForward,
+    Rewind,
+    Next,
+    Previous,
+    Record,
+    VolumeUp,
+    VolumeDown,
+    Mute,
 }
 
-impl Display for Event {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Event::Char(c) => write!(f, "Event::Char('{}')", c),
-            _ => write!(f, "Event::{:?}", self),
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeyKind {
+    Press,
+    /// The key is held down. Terminals without a way to tell report these as presses.
+    Repeat,
+    /// Needs `WinterOptions::key_release` and a terminal that reports them.
+    Release,
+}
+
+/// A key press, `modifiers` is the same as the `KeyState` the event comes with.
+///
+/// ```
+/// use winter::*;
+///
+/// let mut parser = InputParser::new();
+/// parser.feed(b"\x1b[1;5H");
+/// assert_eq!(
+///     parser.next(),
+///     Some((
+///         KeyEvent::new(KeyCode::Home, KeyState(CONTROL)).into(),
+///         KeyState(CONTROL)
+///     ))
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyEvent {
+    pub code: KeyCode,
+    pub modifiers: KeyState,
+    pub kind: KeyKind,
+}
+
+impl KeyEvent {
+    pub const fn new(code: KeyCode, modifiers: KeyState) -> Self {
+        Self {
+            code,
+            modifiers,
+            kind: KeyKind::Press,
         }
     }
 }
 
+impl From<KeyEvent> for Event {
+    fn from(key: KeyEvent) -> Self {
+        Event::Key(key)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MouseKind {
+    Down,
+    Up,
+    /// Moved with `button` held.
+    Drag,
+    /// Moved without a button held.
+    Move,
+    /// A second `Down` on the same cell, it's reported instead of the `Down`.
+    DoubleClick,
+    ScrollUp,
+    ScrollDown,
+    ScrollLeft,
+    ScrollRight,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MouseButton {
+    Left,
+    Right,
+    Middle,
+}
+
+/// Zero-based position inside the window. `button` is `None` for moves and scrolling.
+///
+/// ```
+/// use winter::*;
+///
+/// let mut parser = InputParser::new();
+/// parser.feed(b"\x1b[<32;5;2M");
+/// let Some((Event::Mouse(mouse), _)) = parser.next() else {
+///     panic!();
+/// };
+/// assert_eq!(mouse.kind, MouseKind::Drag);
+/// assert_eq!(mouse.button, Some(MouseButton::Left));
+/// assert_eq!((mouse.x, mouse.y), (4, 1));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MouseEvent {
+    pub kind: MouseKind,
+    pub button: Option<MouseButton>,
+    pub x: u16,
+    pub y: u16,
+    /// The same as the `KeyState` the event comes with.
+    pub modifiers: KeyState,
+}
+
 pub const CONTROL: u32 = 0b0000_0000_0001;
 pub const SHIFT: u32 = 0b0000_0000_0010;
 pub const ALT: u32 = 0b0000_0000_0100;
+///The rest are only reported through the kitty keyboard protocol.
+pub const SUPER: u32 = 0b0000_0000_1000;
+pub const HYPER: u32 = 0b0000_0001_0000;
+pub const META: u32 = 0b0000_0010_0000;
+pub const CAPS_LOCK: u32 = 0b0000_0100_0000;
+pub const NUM_LOCK: u32 = 0b0000_1000_0000;
 
-pub struct KeyState(u32);
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct KeyState(pub u32);
 
 impl KeyState {
     pub fn control(&self) -> bool {
@@ -280,159 +1123,220 @@ impl KeyState {
     pub fn alt(&self) -> bool {
         (self.0 & ALT) != 0
     }
+    pub fn super_key(&self) -> bool {
+        (self.0 & SUPER) != 0
+    }
+    pub fn hyper(&self) -> bool {
+        (self.0 & HYPER) != 0
+    }
+    pub fn meta(&self) -> bool {
+        (self.0 & META) != 0
+    }
+    pub fn caps_lock(&self) -> bool {
+        (self.0 & CAPS_LOCK) != 0
+    }
+    pub fn num_lock(&self) -> bool {
+        (self.0 & NUM_LOCK) != 0
+    }
+    /// Caps lock and num lock removed, for comparing against a fixed state.
+    pub fn without_locks(self) -> KeyState {
+        KeyState(self.0 & !(CAPS_LOCK | NUM_LOCK))
+    }
 }
 
+#[cfg(windows)]
 pub fn key_state(event: INPUT_RECORD) -> KeyState {
-    if event.EventType == KEY_EVENT {
-        let ks = unsafe { event.Event.KeyEvent().dwControlKeyState };
-        let mut state = 0;
-        if ks & SHIFT_PRESSED != 0 {
-            state |= SHIFT;
-        }
-        if ks & LEFT_CTRL_PRESSED != 0 || ks & RIGHT_CTRL_PRESSED != 0 {
-            state |= CONTROL;
-        }
-        if ks & LEFT_ALT_PRESSED != 0 || ks & RIGHT_ALT_PRESSED != 0 {
-            state |= ALT;
-        }
-        KeyState(state)
-    } else {
-        KeyState(0)
+    let ks = match event.EventType {
+        KEY_EVENT => unsafe { event.Event.KeyEvent().dwControlKeyState },
+        MOUSE_EVENT => unsafe { event.Event.MouseEvent().dwControlKeyState },
+        _ => return KeyState(0),
+    };
+    let mut state = 0;
+    if ks & SHIFT_PRESSED != 0 {
+        state |= SHIFT;
     }
+    if ks & LEFT_CTRL_PRESSED != 0 || ks & RIGHT_CTRL_PRESSED != 0 {
+        state |= CONTROL;
+    }
+    if ks & LEFT_ALT_PRESSED != 0 || ks & RIGHT_ALT_PRESSED != 0 {
+        state |= ALT;
+    }
+    KeyState(state)
 }
 
-pub unsafe fn convert_event(event: INPUT_RECORD) -> Option<Event> {
+/// What `convert_event` remembers between records.
+#[cfg(windows)]
+#[derive(Debug, Clone, Default)]
+pub struct RecordState {
+    /// Mouse buttons held as of the last mouse record, to tell presses and releases apart.
+    pub buttons: u32,
+    /// The key that was last pressed and not released yet, pressing it again is a repeat.
+    pub held: Option<u16>,
+    /// First half of a UTF-16 surrogate pair.
+    pub surrogate: Option<u16>,
+}
+
+#[cfg(windows)]
+pub unsafe fn convert_event(event: INPUT_RECORD, state: &mut RecordState) -> Option<Event> {
     match event.EventType {
         KEY_EVENT => {
             let key_event = event.Event.KeyEvent();
-            if key_event.bKeyDown == 1 {
-                const F24: i32 = VK_F1 + 23;
-                let vk = key_event.wVirtualKeyCode;
-                let sc = key_event.wVirtualScanCode;
-                let shift = key_event.dwControlKeyState & SHIFT_PRESSED != 0;
-
-                match vk as i32 {
-                    VK_UP => return Some(Event::Up),
-                    VK_DOWN => return Some(Event::Down),
-                    VK_LEFT => return Some(Event::Left),
-                    VK_RIGHT => return Some(Event::Right),
-                    VK_RETURN => return Some(Event::Enter),
-                    VK_SPACE => return Some(Event::Char(' ')),
-                    VK_BACK => return Some(Event::Backspace),
-                    VK_ESCAPE => return Some(Event::Escape),
-                    VK_TAB => return Some(Event::Tab),
-                    VK_SHIFT | VK_LSHIFT | VK_RSHIFT => return Some(Event::Shift),
-                    VK_CONTROL | VK_LCONTROL | VK_RCONTROL => return Some(Event::Control),
-                    VK_MENU | VK_LMENU | VK_RMENU => return Some(Event::Alt),
-
-                    //TODO: Tilde is kind of an odd ball.
-                    //Might need to handle this one better.
-                    VK_OEM_PLUS if shift => return Some(Event::Char('+')),
-                    VK_OEM_MINUS if shift => return Some(Event::Char('_')),
-                    VK_OEM_3 if shift => return Some(Event::Char('~')),
-                    VK_OEM_4 if shift => return Some(Event::Char('{')),
-                    VK_OEM_6 if shift => return Some(Event::Char('}')),
-                    VK_OEM_5 if shift => return Some(Event::Char('|')),
-                    VK_OEM_1 if shift => return Some(Event::Char(':')),
-                    VK_OEM_7 if shift => return Some(Event::Char('"')),
-                    VK_OEM_COMMA if shift => return Some(Event::Char('<')),
-                    VK_OEM_PERIOD if shift => return Some(Event::Char('>')),
-                    VK_OEM_2 if shift => return Some(Event::Char('?')),
-                    VK_OEM_PLUS => return Some(Event::Char('=')),
-                    VK_OEM_MINUS => return Some(Event::Char('-')),
-
-                    VK_OEM_3 => return Some(Event::Char('`')),
-                    VK_OEM_4 => return Some(Event::Char('[')),
-                    VK_OEM_6 => return Some(Event::Char(']')),
-                    VK_OEM_5 => return Some(Event::Char('\\')),
-                    VK_OEM_1 => return Some(Event::Char(';')),
-                    VK_OEM_7 => return Some(Event::Char('\'')),
-                    VK_OEM_COMMA => return Some(Event::Char(',')),
-                    VK_OEM_PERIOD => return Some(Event::Char('.')),
-                    VK_OEM_2 => return Some(Event::Char('/')),
-
-                    VK_F1..=F24 => return Some(Event::Function((vk - VK_F1 as u16 + 1) as u8)),
-                    // Handle alphanumeric keys (A-Z, 0-9).
-                    0x30..=0x39 | 0x41..=0x5A => {
-                        // Buffer to hold the Unicode characters.
-                        let mut buffer: [u16; 5] = [0; 5];
-
-                        //This would return a multi-character key if this wasn't blank.
-                        let keyboard_state: [u8; 256] = [0; 256];
-
-                        let result = ToUnicode(
-                            vk as u32,
-                            sc as u32,
-                            keyboard_state.as_ptr(),
-                            buffer.as_mut_ptr(),
-                            buffer.len() as i32,
-                            0,
-                        );
-                        match result {
-                            1 if shift => {
-                                let char = buffer[0] as u8 as char;
-                                return Some(Event::Char(match char {
-                                    '1' => '!',
-                                    '2' => '@',
-                                    '3' => '#',
-                                    '4' => '$',
-                                    '5' => '%',
-                                    '6' => '^',
-                                    '7' => '&',
-                                    '8' => '*',
-                                    '9' => '(',
-                                    '0' => ')',
-                                    _ => char.to_ascii_uppercase(),
-                                }));
-                            }
-                            1 => return Some(Event::Char(buffer[0] as u8 as char)),
-                            _ => unimplemented!(),
+            let vk = key_event.wVirtualKeyCode;
+            let unit = *key_event.uChar.UnicodeChar();
+            let mut modifiers = key_state(event.clone());
+
+            let kind = if key_event.bKeyDown == 1 {
+                match state.held.replace(vk) == Some(vk) {
+                    true => KeyKind::Repeat,
+                    false => KeyKind::Press,
+                }
+            } else {
+                if state.held == Some(vk) {
+                    state.held = None;
+                }
+                KeyKind::Release
+            };
+
+            let code = match vk as i32 {
+                VK_UP => KeyCode::Up,
+                VK_DOWN => KeyCode::Down,
+                VK_LEFT => KeyCode::Left,
+                VK_RIGHT => KeyCode::Right,
+                VK_HOME => KeyCode::Home,
+                VK_END => KeyCode::End,
+                VK_PRIOR => KeyCode::PageUp,
+                VK_NEXT => KeyCode::PageDown,
+                VK_INSERT => KeyCode::Insert,
+                VK_DELETE => KeyCode::Delete,
+                VK_RETURN => KeyCode::Enter,
+                VK_BACK => KeyCode::Backspace,
+                VK_ESCAPE => KeyCode::Escape,
+                VK_TAB => KeyCode::Tab,
+                VK_SHIFT | VK_LSHIFT | VK_RSHIFT => KeyCode::Shift,
+                VK_CONTROL | VK_LCONTROL | VK_RCONTROL => KeyCode::Control,
+                VK_MENU | VK_LMENU | VK_RMENU => KeyCode::Alt,
+                VK_F1..=VK_F24 => KeyCode::Function((vk - VK_F1 as u16 + 1) as u8),
+                VK_PLAY => KeyCode::Media(MediaKey::Play),
+                VK_MEDIA_PLAY_PAUSE => KeyCode::Media(MediaKey::PlayPause),
+                VK_MEDIA_STOP => KeyCode::Media(MediaKey::Stop),
+                VK_MEDIA_NEXT_TRACK => KeyCode::Media(MediaKey::Next),
+                VK_MEDIA_PREV_TRACK => KeyCode::Media(MediaKey::Previous),
+                VK_VOLUME_UP => KeyCode::Media(MediaKey::VolumeUp),
+                VK_VOLUME_DOWN => KeyCode::Media(MediaKey::VolumeDown),
+                VK_VOLUME_MUTE => KeyCode::Media(MediaKey::Mute),
+                //The console already applied the layout, dead keys and AltGr to `unit`.
+                _ => match unit {
+                    0xD800..=0xDBFF => {
+                        if kind != KeyKind::Release {
+                            state.surrogate = Some(unit);
                         }
+                        return None;
                     }
-                    _ => return Some(Event::Unknown(vk)),
-                }
-            }
+                    0xDC00..=0xDFFF => {
+                        let high = state.surrogate.take()?;
+                        KeyCode::Char(char::decode_utf16([high, unit]).next()?.ok()?)
+                    }
+                    //A dead key on its own, the next key press has the combined character.
+                    0 if MapVirtualKeyW(vk as u32, MAPVK_VK_TO_CHAR) & 0x8000_0000 != 0 => {
+                        return None
+                    }
+                    //Control turns letters into control characters, and some keys into nothing.
+                    0x00..=0x1f | 0x7f => match vk {
+                        0x30..=0x39 | 0x41..=0x5A => {
+                            KeyCode::Char((vk as u8 as char).to_ascii_lowercase())
+                        }
+                        0x20 => KeyCode::Char(' '),
+                        _ => KeyCode::Unknown(vk),
+                    },
+                    _ => {
+                        let char = char::from_u32(unit as u32)?;
+                        //AltGr is reported as Ctrl+Alt, the character already includes it.
+                        if key_event.dwControlKeyState & RIGHT_ALT_PRESSED != 0 {
+                            modifiers.0 &= !(CONTROL | ALT);
+                        }
+                        if char.is_uppercase() {
+                            modifiers.0 |= SHIFT;
+                        }
+                        KeyCode::Char(char)
+                    }
+                },
+            };
+            return Some(Event::Key(KeyEvent {
+                code,
+                modifiers,
+                kind,
+            }));
         }
         MOUSE_EVENT => {
-            //Keep in mind mouse_event.dwControlKeyState for control clicks.
             let mouse_event = event.Event.MouseEvent();
-            let event_flags = mouse_event.dwEventFlags;
-            let (x, y) = (
-                mouse_event.dwMousePosition.X as u16,
-                mouse_event.dwMousePosition.Y as u16,
-            );
-
-            match event_flags {
-                //TODO: Double click event?
-                0 | DOUBLE_CLICK => match mouse_event.dwButtonState {
-                    FROM_LEFT_1ST_BUTTON_PRESSED => return Some(Event::LeftMouse(x, y)),
-                    RIGHTMOST_BUTTON_PRESSED => return Some(Event::RightMouse(x, y)),
-                    FROM_LEFT_2ND_BUTTON_PRESSED => return Some(Event::MiddleMouse(x, y)),
-                    _ => (),
-                },
-                MOUSE_WHEELED => {
-                    if mouse_event.dwButtonState == 0x800000 {
-                        return Some(Event::ScrollUp);
-                    }
-                    if mouse_event.dwButtonState == 0xff800000 {
-                        return Some(Event::ScrollDown);
+            //The low word is the buttons held, the high word the wheel delta.
+            let buttons = mouse_event.dwButtonState & 0xffff;
+            let delta = (mouse_event.dwButtonState >> 16) as i16;
+            let previous = std::mem::replace(&mut state.buttons, buttons);
+
+            let (kind, button) = match mouse_event.dwEventFlags {
+                0 | DOUBLE_CLICK => {
+                    let pressed = mouse_button(buttons & !previous);
+                    let released = mouse_button(previous & !buttons);
+                    match (pressed, released) {
+                        (Some(_), _) if mouse_event.dwEventFlags == DOUBLE_CLICK => {
+                            (MouseKind::DoubleClick, pressed)
+                        }
+                        (Some(_), _) => (MouseKind::Down, pressed),
+                        (None, Some(_)) => (MouseKind::Up, released),
+                        (None, None) => return None,
                     }
                 }
-                // MOUSE_HWHEELED => {}
-                // MOUSE_MOVED => {}
-                _ => {}
-            }
+                MOUSE_MOVED => match mouse_button(buttons) {
+                    Some(button) => (MouseKind::Drag, Some(button)),
+                    None => (MouseKind::Move, None),
+                },
+                MOUSE_WHEELED if delta > 0 => (MouseKind::ScrollUp, None),
+                MOUSE_WHEELED => (MouseKind::ScrollDown, None),
+                MOUSE_HWHEELED if delta > 0 => (MouseKind::ScrollRight, None),
+                MOUSE_HWHEELED => (MouseKind::ScrollLeft, None),
+                _ => return None,
+            };
+
+            return Some(Event::Mouse(MouseEvent {
+                kind,
+                button,
+                x: mouse_event.dwMousePosition.X as u16,
+                y: mouse_event.dwMousePosition.Y as u16,
+                modifiers: key_state(event.clone()),
+            }));
         }
         WINDOW_BUFFER_SIZE_EVENT => {
             let size = &event.Event.WindowBufferSizeEvent().dwSize;
             return Some(Event::Resize(size.X as u16, size.Y as u16));
         }
+        FOCUS_EVENT => {
+            return match event.Event.FocusEvent().bSetFocus {
+                0 => Some(Event::FocusLost),
+                _ => Some(Event::FocusGained),
+            };
+        }
         _ => (),
     };
     None
 }
 
-pub fn read_input_event(input: *mut c_void) -> INPUT_RECORD {
+///The first button held in `state`, extra buttons are ignored.
+#[cfg(windows)]
+fn mouse_button(state: u32) -> Option<MouseButton> {
+    if state & FROM_LEFT_1ST_BUTTON_PRESSED != 0 {
+        Some(MouseButton::Left)
+    } else if state & RIGHTMOST_BUTTON_PRESSED != 0 {
+        Some(MouseButton::Right)
+    } else if state & FROM_LEFT_2ND_BUTTON_PRESSED != 0 {
+        Some(MouseButton::Middle)
+    } else {
+        None
+    }
+}
+
+#[cfg(windows)]
+pub fn read_input_event(input: *mut c_void) -> Result<INPUT_RECORD, WinterError> {
     let mut record: INPUT_RECORD = unsafe { zeroed() };
 
     //Convert an INPUT_RECORD to an &mut [INPUT_RECORD] of length 1
@@ -442,16 +1346,37 @@ pub fn read_input_event(input: *mut c_void) -> INPUT_RECORD {
     let result =
         unsafe { ReadConsoleInputW(input, buf.as_mut_ptr(), buf.len() as u32, &mut num_records) };
     if result == 0 {
-        panic!("Failed to read input");
+        return Err(WinterError::last_os_error());
     }
 
     //The windows API promises that ReadConsoleInput returns at least 1 element.
     debug_assert!(num_records == 1);
 
-    record
+    Ok(record)
+}
+
+/// Up to `count` queued records without removing them.
+///
+/// # Safety
+///
+/// `input` must be a valid console input handle.
+#[cfg(windows)]
+pub unsafe fn peek_input_events(
+    input: *mut c_void,
+    count: u32,
+) -> Result<Vec<INPUT_RECORD>, WinterError> {
+    let mut records: Vec<INPUT_RECORD> = vec![zeroed(); count as usize];
+    let mut num_records = 0;
+    let result = PeekConsoleInputW(input, records.as_mut_ptr(), count, &mut num_records);
+    if result == 0 {
+        return Err(WinterError::last_os_error());
+    }
+    records.truncate(num_records as usize);
+    Ok(records)
 }
 
-pub fn event_ready(input: *mut c_void, timeout: Option<Duration>) -> bool {
+#[cfg(windows)]
+pub fn event_ready(input: *mut c_void, timeout: Option<Duration>) -> Result<bool, WinterError> {
     let dw_millis = match timeout {
         Some(duration) => duration.as_millis() as u32,
         None => INFINITE,
@@ -462,32 +1387,292 @@ pub fn event_ready(input: *mut c_void, timeout: Option<Duration>) -> bool {
     match output {
         WAIT_OBJECT_0 => {
             // input handle triggered
-            true
+            Ok(true)
         }
         WAIT_TIMEOUT | WAIT_ABANDONED_0 => {
             // timeout elapsed
-            false
+            Ok(false)
         }
-        WAIT_FAILED => panic!("{:#}", std::io::Error::last_os_error()),
-        _ => panic!("WaitForMultipleObjects returned unexpected result."),
+        WAIT_FAILED => Err(WinterError::last_os_error()),
+        _ => Err(WinterError::Io(std::io::Error::other(
+            "WaitForSingleObject returned unexpected result.",
+        ))),
     }
 }
 
-pub fn event_count(input: *mut c_void) -> u32 {
+#[cfg(windows)]
+pub fn event_count(input: *mut c_void) -> Result<u32, WinterError> {
     let mut buf_len: u32 = 0;
     let result = unsafe { GetNumberOfConsoleInputEvents(input, &mut buf_len) };
     if result == 0 {
-        panic!("Could not get number of console input events.");
+        return Err(WinterError::last_os_error());
+    }
+    Ok(buf_len)
+}
+
+///The termios settings from before raw mode was enabled.
+#[cfg(unix)]
+static ORIGINAL_MODE: Mutex<Option<unix::termios>> = Mutex::new(None);
+
+///Read and write ends of the self-pipe used by the signal handler.
+#[cfg(unix)]
+static SIGNAL_PIPE: [AtomicI32; 2] = [AtomicI32::new(-1), AtomicI32::new(-1)];
+
+#[cfg(unix)]
+static RESIZED: AtomicBool = AtomicBool::new(false);
+#[cfg(unix)]
+static STOPPED: AtomicBool = AtomicBool::new(false);
+#[cfg(unix)]
+static CONTINUED: AtomicBool = AtomicBool::new(false);
+
+///Unix terminals only report the cursor through a DSR query, so `cursor_position` is always (0, 0).
+///Use `Backend::cursor_position` instead.
+#[cfg(unix)]
+pub fn info(output: RawHandle) -> Result<Info, WinterError> {
+    unsafe {
+        let mut size = unix::winsize::default();
+        let result = unix::ioctl(output, unix::TIOCGWINSZ, &mut size);
+        if result != 0 {
+            return Err(WinterError::last_os_error());
+        }
+        Ok(Info {
+            buffer_size: (size.ws_col, size.ws_row),
+            window_size: (size.ws_col, size.ws_row),
+            cursor_position: (0, 0),
+            window_position: (0, 0),
+        })
+    }
+}
+
+/// This wraps
+/// [`tcsetattr`](https://man7.org/linux/man-pages/man3/termios.3.html).
+#[cfg(unix)]
+pub fn set_mode(handle: RawHandle, mode: &unix::termios) -> Result<(), WinterError> {
+    unsafe {
+        if unix::tcsetattr(handle, unix::TCSANOW, mode) != 0 {
+            return Err(WinterError::last_os_error());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+pub fn get_mode(handle: RawHandle) -> Result<unix::termios, WinterError> {
+    unsafe {
+        let mut mode: unix::termios = std::mem::zeroed();
+        if unix::tcgetattr(handle, &mut mode) != 0 {
+            return Err(WinterError::last_os_error());
+        }
+        Ok(mode)
+    }
+}
+
+///Disables echo, line buffering and signal keys.
+///The original mode is kept so it can be restored with `disable_raw_mode`.
+#[cfg(unix)]
+pub fn enable_raw_mode(handle: RawHandle) -> Result<(), WinterError> {
+    let original = get_mode(handle)?;
+    let mut raw = original;
+    unsafe { unix::cfmakeraw(&mut raw) };
+    set_mode(handle, &raw)?;
+
+    let mut mode = ORIGINAL_MODE.lock().unwrap();
+    if mode.is_none() {
+        *mode = Some(original);
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+pub fn disable_raw_mode(handle: RawHandle) -> Result<(), WinterError> {
+    if let Some(original) = ORIGINAL_MODE.lock().unwrap().take() {
+        set_mode(handle, &original)?;
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+extern "C" fn on_signal(signal: unix::c_int) {
+    match signal {
+        unix::SIGWINCH => RESIZED.store(true, Ordering::Relaxed),
+        unix::SIGTSTP => STOPPED.store(true, Ordering::Relaxed),
+        unix::SIGCONT => CONTINUED.store(true, Ordering::Relaxed),
+        _ => return,
+    }
+
+    let fd = SIGNAL_PIPE[1].load(Ordering::Relaxed);
+    if fd != -1 {
+        let byte = 1u8;
+        unsafe { unix::write(fd, &byte as *const u8 as *const unix::c_void, 1) };
+    }
+}
+
+///Install a SIGWINCH handler that wakes up `event_ready`, see `catch_job_control` for the others.
+#[cfg(unix)]
+pub fn watch_signals() -> Result<(), WinterError> {
+    if SIGNAL_PIPE[0].load(Ordering::Relaxed) != -1 {
+        return Ok(());
+    }
+
+    unsafe {
+        let mut fds = [-1; 2];
+        if unix::pipe(fds.as_mut_ptr()) != 0 {
+            return Err(WinterError::last_os_error());
+        }
+        for fd in fds {
+            let flags = unix::fcntl(fd, unix::F_GETFL);
+            unix::fcntl(fd, unix::F_SETFL, flags | unix::O_NONBLOCK);
+        }
+        SIGNAL_PIPE[0].store(fds[0], Ordering::Relaxed);
+        SIGNAL_PIPE[1].store(fds[1], Ordering::Relaxed);
+
+        unix::signal(unix::SIGWINCH, signal_handler());
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn signal_handler() -> unix::sighandler_t {
+    on_signal as extern "C" fn(unix::c_int) as unix::sighandler_t
+}
+
+///Report SIGTSTP and SIGCONT through `stopped` and `continued` instead of their default actions.
+#[cfg(unix)]
+pub fn catch_job_control() {
+    unsafe {
+        unix::signal(unix::SIGTSTP, signal_handler());
+        unix::signal(unix::SIGCONT, signal_handler());
+    }
+}
+
+///Give SIGTSTP and SIGCONT their default actions back, so Ctrl+Z stops the process
+///while something else owns the terminal.
+#[cfg(unix)]
+pub fn release_job_control() {
+    unsafe {
+        unix::signal(unix::SIGTSTP, unix::SIG_DFL);
+        unix::signal(unix::SIGCONT, unix::SIG_DFL);
+    }
+}
+
+///Empty the signal pipe, the flags say which signals arrived.
+#[cfg(unix)]
+fn drain_signals() {
+    let fd = SIGNAL_PIPE[0].load(Ordering::Relaxed);
+    if fd == -1 {
+        return;
+    }
+
+    let mut buf = [0u8; 32];
+    while unsafe { unix::read(fd, buf.as_mut_ptr() as *mut unix::c_void, buf.len()) } > 0 {}
+}
+
+///True if the terminal was resized since the last call.
+#[cfg(unix)]
+pub fn resized() -> bool {
+    drain_signals();
+    RESIZED.swap(false, Ordering::Relaxed)
+}
+
+///True if SIGTSTP arrived since the last call, e.g. from `kill -TSTP`.
+#[cfg(unix)]
+pub fn stopped() -> bool {
+    drain_signals();
+    STOPPED.swap(false, Ordering::Relaxed)
+}
+
+///True if the process was continued since the last call. The shell may have changed the terminal modes.
+#[cfg(unix)]
+pub fn continued() -> bool {
+    drain_signals();
+    CONTINUED.swap(false, Ordering::Relaxed)
+}
+
+///Stop the process like the default SIGTSTP action would, returns once the shell continues it.
+#[cfg(unix)]
+pub fn stop_process() {
+    unsafe {
+        let previous = unix::signal(unix::SIGTSTP, unix::SIG_DFL);
+        unix::raise(unix::SIGTSTP);
+        unix::signal(unix::SIGTSTP, previous);
+    }
+    //We know we were continued, the caller restores the terminal itself.
+    CONTINUED.store(false, Ordering::Relaxed);
+}
+
+#[cfg(unix)]
+fn wait(fds: &mut [unix::pollfd], timeout: Option<Duration>) -> Result<bool, WinterError> {
+    let timeout = match timeout {
+        Some(duration) => duration.as_millis().min(i32::MAX as u128) as i32,
+        None => -1,
+    };
+
+    let result = unsafe { unix::poll(fds.as_mut_ptr(), fds.len() as unix::nfds_t, timeout) };
+    if result < 0 {
+        let os_error = std::io::Error::last_os_error();
+        //Interrupted by a signal, usually SIGWINCH. The signal pipe will wake the next poll.
+        if os_error.raw_os_error() == Some(unix::EINTR) {
+            return Ok(false);
+        }
+        return Err(WinterError::Io(os_error));
+    }
+    Ok(result > 0)
+}
+
+#[cfg(unix)]
+pub fn event_ready(input: RawHandle, timeout: Option<Duration>) -> Result<bool, WinterError> {
+    let mut fds = [
+        unix::pollfd {
+            fd: input,
+            events: unix::POLLIN,
+            revents: 0,
+        },
+        unix::pollfd {
+            fd: SIGNAL_PIPE[0].load(Ordering::Relaxed),
+            events: unix::POLLIN,
+            revents: 0,
+        },
+    ];
+    wait(&mut fds, timeout)
+}
+
+///True if `read_input` won't block. `event_ready` also wakes up for signals.
+#[cfg(unix)]
+pub fn input_ready(input: RawHandle) -> Result<bool, WinterError> {
+    let mut fds = [unix::pollfd {
+        fd: input,
+        events: unix::POLLIN,
+        revents: 0,
+    }];
+    wait(&mut fds, Some(Duration::ZERO))
+}
+
+///Read whatever input is available, returns the number of bytes read.
+#[cfg(unix)]
+pub fn read_input(input: RawHandle, buf: &mut [u8]) -> Result<usize, WinterError> {
+    let result = unsafe { unix::read(input, buf.as_mut_ptr() as *mut unix::c_void, buf.len()) };
+    if result < 0 {
+        let os_error = std::io::Error::last_os_error();
+        if os_error.raw_os_error() == Some(unix::EINTR) {
+            return Ok(0);
+        }
+        return Err(WinterError::Io(os_error));
     }
-    buf_len
+    Ok(result as usize)
 }
 
 //https://learn.microsoft.com/en-us/windows/console/console-virtual-terminal-sequences
 
 ///Clear the entire screen, using `cmd /c cls`.
+#[cfg(windows)]
 pub fn clear_all() {
     Command::new("cmd").args(["/C", "cls"]).status().unwrap();
 }
+///Clear the entire screen, using `clear`.
+#[cfg(unix)]
+pub fn clear_all() {
+    Command::new("clear").status().unwrap();
+}
 pub fn show_cursor<W: Write>(w: &mut W) {
     write!(w, "\x1b[?25h").unwrap();
 }
@@ -498,6 +1683,60 @@ pub fn hide_cursor<W: Write>(w: &mut W) {
 pub fn move_to<W: Write>(w: &mut W, x: u16, y: u16) {
     write!(w, "\x1b[{};{}H", y, x).unwrap();
 }
+///Control characters would end the sequence early.
+fn strip_controls(text: &str) -> String {
+    text.chars().filter(|c| !c.is_control()).collect()
+}
+///OSC 0, sets both the window and the tab (icon) title.
+pub fn set_title<W: Write>(w: &mut W, title: &str) {
+    write!(w, "\x1b]0;{}\x07", strip_controls(title)).unwrap();
+}
+///Save the title on the XTWINOPS title stack.
+pub fn push_title<W: Write>(w: &mut W) {
+    write!(w, "\x1b[22;0t").unwrap();
+}
+pub fn pop_title<W: Write>(w: &mut W) {
+    write!(w, "\x1b[23;0t").unwrap();
+}
+pub fn bell<W: Write>(w: &mut W) {
+    write!(w, "\x07").unwrap();
+}
+pub fn notify<W: Write>(w: &mut W, notification: Notification, title: &str, body: &str) {
+    let (title, body) = (strip_controls(title), strip_controls(body));
+    match notification {
+        //OSC 9 only has a message.
+        Notification::Osc9 if body.is_empty() => write!(w, "\x1b]9;{}\x07", title),
+        Notification::Osc9 => write!(w, "\x1b]9;{}: {}\x07", title, body),
+        //Fields are separated by `;`.
+        Notification::Osc777 => write!(
+            w,
+            "\x1b]777;notify;{};{}\x07",
+            title.replace(';', ","),
+            body
+        ),
+    }
+    .unwrap();
+}
+///OSC 52, the terminal puts `text` on the system clipboard. Works over SSH too.
+pub fn copy_to_clipboard<W: Write>(w: &mut W, text: &str) {
+    write!(w, "\x1b]52;c;{}\x07", base64::encode(text.as_bytes())).unwrap();
+}
+///Ask for the clipboard, the reply is read as `Event::Clipboard`.
+pub fn request_clipboard<W: Write>(w: &mut W) {
+    write!(w, "\x1b]52;c;?\x07").unwrap();
+}
+///OSC 8, the text written until `end_hyperlink` opens `target` when clicked.
+///Control characters are dropped so the target can't end the sequence early.
+pub fn start_hyperlink<W: Write>(w: &mut W, target: &str) {
+    write!(w, "\x1b]8;;{}\x1b\\", strip_controls(target)).unwrap();
+}
+pub fn end_hyperlink<W: Write>(w: &mut W) {
+    write!(w, "\x1b]8;;\x1b\\").unwrap();
+}
+///DECSCUSR, some terminals only support the blinking half through `show_blinking`.
+pub fn set_cursor_shape<W: Write>(w: &mut W, shape: CursorShape) {
+    write!(w, "\x1b[{} q", shape as u8).unwrap();
+}
 pub fn show_blinking<W: Write>(w: &mut W) {
     write!(w, "\x1b[?12h").unwrap();
 }
@@ -511,7 +1750,55 @@ pub fn show_alternate_screen<W: Write>(w: &mut W) {
 pub fn hide_alternate_screen<W: Write>(w: &mut W) {
     write!(w, "\x1b[?1049l").unwrap();
 }
+///Report clicks, drags, movement and scrolling using SGR (1006) encoded mouse events.
+pub fn enable_mouse_capture<W: Write>(w: &mut W) {
+    write!(w, "\x1b[?1000h\x1b[?1002h\x1b[?1003h\x1b[?1006h").unwrap();
+}
+pub fn disable_mouse_capture<W: Write>(w: &mut W) {
+    write!(w, "\x1b[?1006l\x1b[?1003l\x1b[?1002l\x1b[?1000l").unwrap();
+}
+///Pasted text is wrapped in `CSI 200 ~` and `CSI 201 ~`.
+pub fn enable_bracketed_paste<W: Write>(w: &mut W) {
+    write!(w, "\x1b[?2004h").unwrap();
+}
+pub fn disable_bracketed_paste<W: Write>(w: &mut W) {
+    write!(w, "\x1b[?2004l").unwrap();
+}
+///Report focus changes as `CSI I` and `CSI O`.
+pub fn enable_focus_reporting<W: Write>(w: &mut W) {
+    write!(w, "\x1b[?1004h").unwrap();
+}
+pub fn disable_focus_reporting<W: Write>(w: &mut W) {
+    write!(w, "\x1b[?1004l").unwrap();
+}
+///Kitty keyboard flags `Winter` pushes: disambiguate escape codes (1),
+///report event types (2) and report alternate keys (4).
+pub const KEYBOARD_FLAGS: u8 = 0b111;
+///Set while `Console` has flags pushed, so they are popped once by whichever of
+///`leave` and `uninit` comes first.
+static KEYBOARD_FLAGS_PUSHED: AtomicBool = AtomicBool::new(false);
+///Push `flags` on the kitty keyboard protocol stack, keys then arrive as `CSI u` sequences.
+pub fn push_keyboard_flags<W: Write>(w: &mut W, flags: u8) {
+    write!(w, "\x1b[>{}u", flags).unwrap();
+}
+pub fn pop_keyboard_flags<W: Write>(w: &mut W) {
+    write!(w, "\x1b[<u").unwrap();
+}
+///Hold output until `end_synchronized_update`, terminals without mode 2026 ignore it.
+pub fn begin_synchronized_update<W: Write>(w: &mut W) {
+    write!(w, "\x1b[?2026h").unwrap();
+}
+pub fn end_synchronized_update<W: Write>(w: &mut W) {
+    write!(w, "\x1b[?2026l").unwrap();
+}
 
+///Limit scrolling to rows `top..=bottom`, one-based. Moves the cursor to the top left.
+pub fn set_scroll_region<W: Write>(w: &mut W, top: u16, bottom: u16) {
+    write!(w, "\x1b[{};{}r", top, bottom).unwrap();
+}
+pub fn reset_scroll_region<W: Write>(w: &mut W) {
+    write!(w, "\x1b[r").unwrap();
+}
 pub fn shift_up<W: Write>(w: &mut W, amount: u16) {
     write!(w, "\x1b[{}S", amount).unwrap();
 }
@@ -4,7 +4,7 @@ use std::{
     io::{stdin, stdout, Write},
     time::{Duration, Instant},
 };
-use winter::*;
+use winter::{keymap::*, *};
 
 pub fn settings(area: Rect, buf: &mut Buffer) {
     //TODO: I liked the old item menu bold selections.
@@ -136,49 +136,43 @@ fn draw(buf: &mut Buffer) {
     }
 }
 
-fn main() {
-    let mut winter = Winter::new();
-
-    //Prevents panic messages from being hidden.
-    let orig_hook = std::panic::take_hook();
-    std::panic::set_hook(Box::new(move |panic_info| {
-        uninit(&mut stdout(), &mut stdin());
-        orig_hook(panic_info);
-        std::process::exit(1);
-    }));
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Action {
+    Up,
+    Down,
+}
 
-    let mut index = 0;
-    let mut cursor = (10, 5);
+struct Demo {
+    index: usize,
+    keymap: Keymap<Action>,
+}
 
-    loop {
-        //Draw the widgets into the front buffer.
-        draw(winter.buffer());
+impl App for Demo {
+    fn update(&mut self, event: Event, state: KeyState) -> bool {
+        match self.keymap.get(&event, state) {
+            Some(Action::Up) if self.index != 0 => self.index -= 1,
+            Some(Action::Down) => self.index += 1,
+            _ => return false,
+        }
+        true
+    }
+    fn draw(&mut self, buf: &mut Buffer) {
+        draw(buf);
 
         // show_cursor(&mut stdout);
         // show_blinking(&mut stdout);
         // browser(viewport, &mut buffers[current], None);
         // settings(viewport, &mut buffers[current]);
-
-        //Handle events
-        {
-            if let Some((event, state)) = winter.poll() {
-                // println!("{}", event);
-
-                //TODO: I might want a class or trait or something to handle this pattern.
-                if event == Event::Up {
-                    if index != 0 {
-                        index -= 1;
-                    }
-                }
-                if event == Event::Down {
-                    index += 1;
-                }
-                if event == Event::Char('c') && state.control() || event == Event::Escape {
-                    break;
-                }
-            }
-        }
-
-        winter.draw();
     }
 }
+
+fn main() -> Result<(), WinterError> {
+    runner().quit_key("esc".parse()?).run(&mut Demo {
+        index: 0,
+        keymap: keymap()
+            .bind("up".parse()?, Action::Up)
+            .bind("k".parse()?, Action::Up)
+            .bind("down".parse()?, Action::Down)
+            .bind("j".parse()?, Action::Down),
+    })
+}
@@ -1,6 +1,7 @@
+use crate::WinterError;
 use bitflags::bitflags;
 
-#[derive(Debug, Clone, PartialEq, Eq, Copy, Default)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Copy, Default)]
 pub enum Color {
     Black,
     Red,
@@ -76,8 +77,36 @@ impl Color {
     }
 }
 
+impl std::str::FromStr for Color {
+    type Err = WinterError;
+
+    ///The same names `Debug` prints.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "Black" => Color::Black,
+            "Red" => Color::Red,
+            "Green" => Color::Green,
+            "Yellow" => Color::Yellow,
+            "Blue" => Color::Blue,
+            "Magenta" => Color::Magenta,
+            "Cyan" => Color::Cyan,
+            "White" => Color::White,
+            "BrightBlack" => Color::BrightBlack,
+            "BrightRed" => Color::BrightRed,
+            "BrightGreen" => Color::BrightGreen,
+            "BrightYellow" => Color::BrightYellow,
+            "BrightBlue" => Color::BrightBlue,
+            "BrightMagenta" => Color::BrightMagenta,
+            "BrightCyan" => Color::BrightCyan,
+            "BrightWhite" => Color::BrightWhite,
+            "Reset" => Color::Reset,
+            _ => return Err(WinterError::InvalidColor(s.to_string())),
+        })
+    }
+}
+
 bitflags! {
-    #[derive(Debug, Clone, PartialEq, Eq, Copy, Default)]
+    #[derive(Debug, Clone, PartialEq, Eq, Hash, Copy, Default)]
     pub struct Modifier: u16 {
         const BOLD              = 0b0000_0000_0001;
         const DIM               = 0b0000_0000_0010;
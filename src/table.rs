@@ -169,7 +169,7 @@ impl<'a> Table<'a> {
 
             let (start, end) = if selection <= half {
                 (0, real_end)
-            } else if height % 2 == 0 {
+            } else if height.is_multiple_of(2) {
                 (selection - half, (selection + 2) + half)
             } else {
                 (selection - half, (selection + 1) + half)
@@ -194,7 +194,7 @@ impl<'a> Table<'a> {
     ///    //Check if you clicked on the header.
     ///    if y >= header_height {
     ///        let index = (y - header_height) as usize + start;
-
+    ///
     ///        //Make sure you didn't click on the seek bar
     ///        //and that the song index exists.
     ///        if index < player.songs.len()
@@ -280,7 +280,7 @@ impl<'a> Table<'a> {
         for (i, row) in self.rows.iter().enumerate().skip(start).take(end - start) {
             let (x, y) = (table_area.left(), table_area.top() + current_height);
             current_height += row.total_height();
-            let selected = state.map_or(false, |s| s == i);
+            let selected = state == Some(i);
 
             let mut x = if has_selection {
                 let symbol = if selected {
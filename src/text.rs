@@ -59,11 +59,11 @@ impl<'a> Deref for Line<'a> {
     }
 }
 
-impl<'a> Into<Line<'a>> for Text<'a> {
-    fn into(self) -> Line<'a> {
+impl<'a> From<Text<'a>> for Line<'a> {
+    fn from(text: Text<'a>) -> Line<'a> {
         Line {
-            style: Some(self.style),
-            lines: Box::new([self]),
+            style: Some(text.style),
+            lines: Box::new([text]),
             block: None,
             alignment: Alignment::Left,
             scroll: false,
@@ -71,10 +71,10 @@ impl<'a> Into<Line<'a>> for Text<'a> {
     }
 }
 
-impl<'a> Into<Line<'a>> for &'a [Text<'a>] {
-    fn into(self) -> Line<'a> {
+impl<'a> From<&'a [Text<'a>]> for Line<'a> {
+    fn from(lines: &'a [Text<'a>]) -> Line<'a> {
         Line {
-            lines: self.into(),
+            lines: lines.into(),
             block: None,
             style: None,
             alignment: Alignment::Left,
@@ -116,6 +116,7 @@ macro_rules! text {
         Text {
             inner: format_args!($($args)*).to_string().into(),
             style: Style::default(),
+            link: None,
         }
     };
 }
@@ -124,6 +125,8 @@ macro_rules! text {
 pub struct Text<'a> {
     pub inner: std::borrow::Cow<'a, str>,
     pub style: Style,
+    /// Opened by the terminal when the text is clicked, see `Stylize::link`.
+    pub link: Option<std::borrow::Cow<'a, str>>,
 }
 
 impl<'a> Text<'a> {
@@ -154,6 +157,17 @@ pub trait Stylize<'a> {
     fn fg(self, fg: Color) -> Text<'a>;
     fn bg(self, bg: Color) -> Text<'a>;
     fn style(self, style: Style) -> Text<'a>;
+    /// Make the text a hyperlink (OSC 8), terminals without support show it as plain text.
+    ///
+    /// ```
+    /// use winter::*;
+    ///
+    /// let text = "docs".link("https://example.com/docs");
+    /// let mut buf = Buffer::empty(Rect::new(0, 0, 4, 1));
+    /// lines!(text).draw(buf.area, &mut buf);
+    /// assert_eq!(buf.content[0].link.as_deref(), Some("https://example.com/docs"));
+    /// ```
+    fn link(self, target: impl Into<std::borrow::Cow<'a, str>>) -> Text<'a>;
 }
 
 macro_rules! modifier {
@@ -164,6 +178,7 @@ macro_rules! modifier {
                     Text {
                         inner: std::borrow::Cow::from(self),
                         style: $name(),
+                        link: None,
                     }
                 })*
             };
@@ -196,6 +211,11 @@ macro_rules! modifier {
                     text.style = style;
                     text
                 }
+                fn link(self, target: impl Into<std::borrow::Cow<'a, str>>) -> Text<'a> {
+                    let mut text = Into::<Text>::into(self);
+                    text.link = Some(target.into());
+                    text
+                }
             }
         }
 
@@ -231,19 +251,20 @@ modifier! {
 macro_rules! impl_into {
     ($($t:ty),*) => {
         $(
-            impl<'a> Into<Text<'a>> for $t {
-                fn into(self) -> Text<'a> {
+            impl<'a> From<$t> for Text<'a> {
+                fn from(text: $t) -> Text<'a> {
                     Text {
-                        inner: std::borrow::Cow::from(self),
+                        inner: std::borrow::Cow::from(text),
                         style: Style::default(),
+                        link: None,
                     }
                 }
             }
 
-            impl<'a> Into<Line<'a>> for $t {
-                fn into(self) -> Line<'a> {
+            impl<'a> From<$t> for Line<'a> {
+                fn from(text: $t) -> Line<'a> {
                     Line {
-                        lines: Box::new([self.into()]),
+                        lines: Box::new([text.into()]),
                         block: None,
                         style: None,
                         alignment: Alignment::Left,
@@ -0,0 +1,129 @@
+pub use std::os::raw::{c_int, c_ulong, c_void};
+use std::os::unix::io::AsRawFd;
+
+pub type RawHandle = c_int;
+
+//The constants and struct layouts below are only right for these two.
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+compile_error!("the Unix backend only supports Linux and macOS");
+
+#[cfg(target_os = "linux")]
+pub type tcflag_t = u32;
+#[cfg(target_os = "macos")]
+pub type tcflag_t = c_ulong;
+
+#[cfg(target_os = "linux")]
+pub type speed_t = u32;
+#[cfg(target_os = "macos")]
+pub type speed_t = c_ulong;
+
+#[cfg(target_os = "linux")]
+pub type nfds_t = c_ulong;
+#[cfg(target_os = "macos")]
+pub type nfds_t = u32;
+
+///A handler function or `SIG_DFL`.
+pub type sighandler_t = usize;
+
+extern "C" {
+    pub fn tcgetattr(fd: c_int, termios: *mut termios) -> c_int;
+    pub fn tcsetattr(fd: c_int, optional_actions: c_int, termios: *const termios) -> c_int;
+    pub fn cfmakeraw(termios: *mut termios);
+    pub fn ioctl(fd: c_int, request: c_ulong, ...) -> c_int;
+    pub fn isatty(fd: c_int) -> c_int;
+    pub fn poll(fds: *mut pollfd, nfds: nfds_t, timeout: c_int) -> c_int;
+    pub fn read(fd: c_int, buf: *mut c_void, count: usize) -> isize;
+    pub fn write(fd: c_int, buf: *const c_void, count: usize) -> isize;
+    pub fn pipe(fds: *mut c_int) -> c_int;
+    pub fn fcntl(fd: c_int, cmd: c_int, ...) -> c_int;
+    pub fn signal(signum: c_int, handler: sighandler_t) -> sighandler_t;
+    pub fn raise(signum: c_int) -> c_int;
+}
+
+#[cfg(target_os = "linux")]
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct termios {
+    pub c_iflag: tcflag_t,
+    pub c_oflag: tcflag_t,
+    pub c_cflag: tcflag_t,
+    pub c_lflag: tcflag_t,
+    pub c_line: u8,
+    pub c_cc: [u8; 32],
+    pub c_ispeed: speed_t,
+    pub c_ospeed: speed_t,
+}
+
+#[cfg(target_os = "macos")]
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct termios {
+    pub c_iflag: tcflag_t,
+    pub c_oflag: tcflag_t,
+    pub c_cflag: tcflag_t,
+    pub c_lflag: tcflag_t,
+    pub c_cc: [u8; 20],
+    pub c_ispeed: speed_t,
+    pub c_ospeed: speed_t,
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+#[repr(C)]
+pub struct winsize {
+    pub ws_row: u16,
+    pub ws_col: u16,
+    pub ws_xpixel: u16,
+    pub ws_ypixel: u16,
+}
+
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct pollfd {
+    pub fd: c_int,
+    pub events: i16,
+    pub revents: i16,
+}
+
+pub const STDIN_FILENO: c_int = 0;
+pub const STDOUT_FILENO: c_int = 1;
+
+pub const TCSANOW: c_int = 0;
+
+#[cfg(target_os = "linux")]
+pub const TIOCGWINSZ: c_ulong = 0x5413;
+#[cfg(target_os = "macos")]
+pub const TIOCGWINSZ: c_ulong = 0x40087468;
+
+pub const POLLIN: i16 = 0x0001;
+
+pub const F_GETFL: c_int = 3;
+pub const F_SETFL: c_int = 4;
+#[cfg(target_os = "linux")]
+pub const O_NONBLOCK: c_int = 0o4000;
+#[cfg(target_os = "macos")]
+pub const O_NONBLOCK: c_int = 0x0004;
+
+pub const SIGWINCH: c_int = 28;
+#[cfg(target_os = "linux")]
+pub const SIGTSTP: c_int = 20;
+#[cfg(target_os = "macos")]
+pub const SIGTSTP: c_int = 18;
+#[cfg(target_os = "linux")]
+pub const SIGCONT: c_int = 18;
+#[cfg(target_os = "macos")]
+pub const SIGCONT: c_int = 19;
+
+pub const SIG_DFL: sighandler_t = 0;
+
+pub const EINTR: i32 = 4;
+
+///Mirrors `std::os::windows::io::AsRawHandle` so call sites look the same on every platform.
+pub trait AsRawHandle {
+    fn as_raw_handle(&self) -> RawHandle;
+}
+
+impl<T: AsRawFd> AsRawHandle for T {
+    fn as_raw_handle(&self) -> RawHandle {
+        self.as_raw_fd()
+    }
+}
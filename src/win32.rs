@@ -10,6 +10,12 @@ extern "system" {
         nLength: u32,
         lpNumberOfEventsRead: *mut u32,
     ) -> i32;
+    pub fn PeekConsoleInputW(
+        hConsoleInput: *mut c_void,
+        lpBuffer: *mut INPUT_RECORD,
+        nLength: u32,
+        lpNumberOfEventsRead: *mut u32,
+    ) -> i32;
     pub fn SetConsoleMode(hConsoleHandle: HANDLE, dwMode: u32) -> i32;
     pub fn GetConsoleMode(hConsoleHandle: HANDLE, lpMode: *mut u32) -> i32;
     pub fn GetNumberOfConsoleInputEvents(hConsoleInput: HANDLE, lpNumberOfEvents: *mut u32) -> i32;
@@ -38,6 +44,7 @@ extern "system" {
         cchBuff: i32,
         wFlags: u32,
     ) -> i32;
+    pub fn MapVirtualKeyW(uCode: u32, uMapType: u32) -> u32;
 
 }
 
@@ -309,6 +316,8 @@ pub const VK_NONAME: i32 = 0xFC;
 pub const VK_PA1: i32 = 0xFD;
 pub const VK_OEM_CLEAR: i32 = 0xFE;
 
+pub const MAPVK_VK_TO_CHAR: u32 = 2;
+
 pub const RIGHT_ALT_PRESSED: u32 = 0x0001;
 pub const LEFT_ALT_PRESSED: u32 = 0x0002;
 pub const RIGHT_CTRL_PRESSED: u32 = 0x0004;
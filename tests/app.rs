@@ -0,0 +1,95 @@
+use std::time::Duration;
+use winter::{keymap::KeyBinding, *};
+
+#[derive(Default)]
+struct Counter {
+    keys: Vec<char>,
+    ticks: usize,
+    frames: usize,
+    quit_after_ticks: Option<usize>,
+}
+
+impl App for Counter {
+    fn update(&mut self, event: Event, _: KeyState) -> bool {
+        match event {
+            Event::Key(KeyEvent {
+                code: KeyCode::Char(c),
+                ..
+            }) => {
+                self.keys.push(c);
+                true
+            }
+            _ => false,
+        }
+    }
+    fn draw(&mut self, buf: &mut Buffer) {
+        self.frames += 1;
+        lines!(self.keys.iter().collect::<String>()).draw(buf.area, buf);
+    }
+    fn tick(&mut self) -> bool {
+        self.ticks += 1;
+        false
+    }
+    fn should_quit(&self) -> bool {
+        self.quit_after_ticks
+            .is_some_and(|ticks| self.ticks >= ticks)
+    }
+}
+
+fn char_key(c: char, state: u32) -> Event {
+    KeyEvent::new(KeyCode::Char(c), KeyState(state)).into()
+}
+
+#[test]
+fn runner_updates_until_a_quit_key() {
+    let mut backend = TestBackend::new(5, 1);
+    backend.push_event(char_key('a', 0), KeyState(0));
+    backend.push_event(char_key('b', 0), KeyState(0));
+    backend.push_event(char_key('c', CONTROL), KeyState(CONTROL));
+    backend.push_event(char_key('d', 0), KeyState(0));
+    let mut winter = Winter::with_backend(backend).unwrap();
+
+    let mut app = Counter::default();
+    runner().max_fps(0).run_with(&mut winter, &mut app).unwrap();
+    assert_eq!(app.keys, ['a', 'b']);
+    assert_eq!(winter.backend.lines(), ["ab   "]);
+    //The event after the quit key is never read.
+    assert_eq!(winter.backend.events.len(), 1);
+}
+
+#[test]
+fn runner_only_redraws_after_changes() {
+    let mut backend = TestBackend::new(5, 1);
+    backend.push_event(Event::FocusGained, KeyState(0));
+    backend.push_event(char_key('a', 0), KeyState(0));
+    let mut winter = Winter::with_backend(backend).unwrap();
+
+    let mut app = Counter {
+        quit_after_ticks: Some(3),
+        ..Default::default()
+    };
+    runner()
+        .max_fps(0)
+        .tick_rate(Duration::from_millis(1))
+        .run_with(&mut winter, &mut app)
+        .unwrap();
+    //The first frame and the one after `a`, the focus event and ticks changed nothing.
+    assert_eq!(app.frames, 2);
+    assert_eq!(app.ticks, 3);
+}
+
+#[test]
+fn runner_quit_keys_can_be_replaced() {
+    let mut backend = TestBackend::new(1, 1);
+    backend.push_event(char_key('c', CONTROL), KeyState(CONTROL));
+    //Num lock being on doesn't change the key.
+    backend.push_event(char_key('q', NUM_LOCK), KeyState(NUM_LOCK));
+    let mut winter = Winter::with_backend(backend).unwrap();
+
+    let mut app = Counter::default();
+    runner()
+        .quit_keys(&[KeyBinding::new(KeyCode::Char('q'), KeyState(0))])
+        .run_with(&mut winter, &mut app)
+        .unwrap();
+    assert_eq!(app.keys, ['c']);
+}
@@ -0,0 +1,167 @@
+use winter::*;
+
+#[test]
+fn backend_is_set_up_and_restored() {
+    let mut winter = Winter::with_backend(TestBackend::new(4, 2)).unwrap();
+    assert!(winter.backend.initialized);
+    assert!(winter.backend.raw_mode);
+    assert!(winter.backend.alternate_screen);
+    assert!(!winter.backend.cursor_visible);
+    assert_eq!(winter.viewport, Rect::new(0, 0, 4, 2));
+
+    winter.suspend().unwrap();
+    assert!(!winter.backend.raw_mode);
+    assert!(!winter.backend.alternate_screen);
+    assert!(winter.backend.cursor_visible);
+}
+
+#[test]
+fn events_are_polled_in_order() {
+    let a: Event = KeyEvent::new(KeyCode::Char('a'), KeyState(0)).into();
+    let b: Event = KeyEvent::new(KeyCode::Char('b'), KeyState(0)).into();
+    let mut backend = TestBackend::new(1, 1);
+    backend.push_event(a.clone(), KeyState(0));
+    backend.push_event(b.clone(), KeyState(0));
+
+    let mut winter = Winter::with_backend(backend).unwrap();
+    assert_eq!(winter.poll().unwrap(), Some((a, KeyState(0))));
+    assert_eq!(winter.poll().unwrap(), Some((b, KeyState(0))));
+    assert_eq!(winter.poll().unwrap(), None);
+}
+
+#[test]
+fn draw_only_sends_changed_cells() {
+    let mut winter = Winter::with_backend(TestBackend::new(5, 1)).unwrap();
+    lines!("hello").draw(winter.viewport, winter.buffer());
+    winter.draw().unwrap();
+    assert_eq!(winter.backend.lines(), ["hello"]);
+    assert_eq!(winter.stats.cells, 5);
+
+    lines!("help").draw(winter.viewport, winter.buffer());
+    winter.draw().unwrap();
+    assert_eq!(winter.backend.lines(), ["help "]);
+    assert_eq!(winter.stats.cells, 2);
+}
+
+#[test]
+fn resize_is_reported_and_redrawn() {
+    let mut winter = Winter::with_backend(TestBackend::new(3, 1)).unwrap();
+    lines!("abc").draw(winter.viewport, winter.buffer());
+    winter.draw().unwrap();
+
+    winter.backend.resize(4, 2);
+    assert_eq!(
+        winter.poll().unwrap(),
+        Some((Event::Resize(4, 2), KeyState(0)))
+    );
+
+    //The next frame is drawn at the new size.
+    winter.draw().unwrap();
+    assert_eq!(winter.viewport, Rect::new(0, 0, 4, 2));
+    lines!("abc").draw(winter.viewport, winter.buffer());
+    winter.draw().unwrap();
+    assert_eq!(winter.backend.lines(), ["abc ", "    "]);
+}
+
+#[test]
+fn options_choose_the_modes() {
+    let options = WinterOptions::new()
+        .alternate_screen(false)
+        .mouse_capture(false)
+        .hide_cursor(false);
+    let mut winter = Winter::with_backend_options(TestBackend::new(1, 1), options).unwrap();
+    assert!(winter.backend.raw_mode);
+    assert!(!winter.backend.alternate_screen);
+    assert!(!winter.backend.mouse_capture);
+    assert!(winter.backend.cursor_visible);
+
+    winter.set_mouse_capture(true).unwrap();
+    assert!(winter.backend.mouse_capture);
+    winter.set_raw_mode(false).unwrap();
+    assert!(!winter.backend.raw_mode);
+    winter.set_alternate_screen(true).unwrap();
+    assert!(winter.backend.alternate_screen);
+
+    //Modes turned on at runtime are turned off again.
+    winter.suspend().unwrap();
+    assert!(!winter.backend.mouse_capture);
+    assert!(!winter.backend.alternate_screen);
+    winter.resume().unwrap();
+    assert!(winter.backend.mouse_capture);
+    assert!(!winter.backend.raw_mode);
+}
+
+#[test]
+fn job_control_follows_suspend() {
+    let mut winter = Winter::with_backend(TestBackend::new(1, 1)).unwrap();
+    assert!(winter.backend.job_control);
+
+    //A child process run while suspended gets the default Ctrl+Z behaviour.
+    winter.suspend().unwrap();
+    assert!(!winter.backend.job_control);
+    winter.resume().unwrap();
+    assert!(winter.backend.job_control);
+}
+
+fn written(winter: &mut Winter<WriterBackend<Vec<u8>>>) -> String {
+    String::from_utf8(std::mem::take(&mut winter.backend.writer)).unwrap()
+}
+
+#[test]
+fn writer_backend_gets_the_escape_sequences() {
+    let options = WinterOptions::new().synchronized_output(false);
+    let mut winter =
+        Winter::with_backend_options(WriterBackend::new(Vec::new(), 5, 1), options).unwrap();
+    let setup = written(&mut winter);
+    assert!(setup.contains("\x1b[?1049h"), "{setup:?}");
+    assert!(setup.contains("\x1b[?1006h"), "{setup:?}");
+    assert!(setup.contains("\x1b[?25l"), "{setup:?}");
+
+    lines!("hello").draw(winter.viewport, winter.buffer());
+    winter.draw().unwrap();
+    assert!(written(&mut winter).contains("hello"));
+
+    //Only the changed cell is written again.
+    lines!("hullo").draw(winter.viewport, winter.buffer());
+    winter.draw().unwrap();
+    let frame = written(&mut winter);
+    assert!(frame.contains('u') && !frame.contains("llo"), "{frame:?}");
+
+    winter.suspend().unwrap();
+    let teardown = written(&mut winter);
+    assert!(teardown.contains("\x1b[?1049l"), "{teardown:?}");
+    assert!(teardown.contains("\x1b[?25h"), "{teardown:?}");
+}
+
+#[test]
+fn writer_backend_events_and_size() {
+    let mut winter = Winter::with_backend(WriterBackend::new(Vec::new(), 2, 2)).unwrap();
+    winter.backend.resize(3, 1);
+    assert_eq!(
+        winter.poll().unwrap(),
+        Some((Event::Resize(3, 1), KeyState(0)))
+    );
+    assert_eq!(winter.poll().unwrap(), None);
+    winter.draw().unwrap();
+    assert_eq!(winter.viewport, Rect::new(0, 0, 3, 1));
+}
+
+struct BrokenPipe;
+
+impl std::io::Write for BrokenPipe {
+    fn write(&mut self, _: &[u8]) -> std::io::Result<usize> {
+        Err(std::io::ErrorKind::BrokenPipe.into())
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn writer_backend_errors_are_returned() {
+    //Reported through `flush` instead of panicking, and `Drop` ignores the next one.
+    let result = Winter::with_backend(WriterBackend::new(BrokenPipe, 2, 2));
+    assert!(
+        matches!(result, Err(WinterError::Io(err)) if err.kind() == std::io::ErrorKind::BrokenPipe)
+    );
+}
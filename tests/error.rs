@@ -0,0 +1,35 @@
+use std::error::Error;
+use winter::*;
+
+#[test]
+fn io_errors_convert() {
+    fn read() -> Result<(), WinterError> {
+        Err(std::io::Error::new(std::io::ErrorKind::NotFound, "gone"))?;
+        Ok(())
+    }
+    let err = read().unwrap_err();
+    assert!(matches!(&err, WinterError::Io(io) if io.kind() == std::io::ErrorKind::NotFound));
+    assert_eq!(err.to_string(), "gone");
+    assert_eq!(err.source().unwrap().to_string(), "gone");
+}
+
+#[test]
+fn out_of_bounds() {
+    let buf = Buffer::empty(Rect::new(1, 1, 2, 2));
+    let err = buf.index_of(3, 1).unwrap_err();
+    assert!(matches!(
+        err,
+        WinterError::OutOfBounds { x: 3, y: 1, area } if area == buf.area
+    ));
+    assert!(err.source().is_none());
+    assert!(buf.index_of(2, 2).is_ok());
+}
+
+#[test]
+fn invalid_layout() {
+    let area = Rect::new(0, 0, 10, 10);
+    let err = layout_margin(area, Vertical, &[Fill, Length(1), Fill], (0, 0)).unwrap_err();
+    assert_eq!(err.to_string(), "Cannot have multiple fill constraints.");
+    let err = layout_margin(area, Vertical, &[Length(1), Fill, Length(1)], (0, 0)).unwrap_err();
+    assert!(matches!(err, WinterError::InvalidLayout(_)));
+}
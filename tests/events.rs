@@ -0,0 +1,97 @@
+use std::{
+    io::Write,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
+use winter::{events::*, *};
+
+///Counts how often the input thread polls it.
+struct Polls(Arc<AtomicUsize>);
+
+impl Write for Polls {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Backend for Polls {
+    fn size(&mut self) -> Result<(u16, u16), WinterError> {
+        Ok((1, 1))
+    }
+    fn init(&mut self) -> Result<(), WinterError> {
+        Ok(())
+    }
+    fn uninit(&mut self) -> Result<(), WinterError> {
+        Ok(())
+    }
+    fn poll(&mut self, _: Duration) -> Result<Option<(Event, KeyState)>, WinterError> {
+        self.0.fetch_add(1, Ordering::SeqCst);
+        thread::sleep(Duration::from_millis(1));
+        Ok(None)
+    }
+    fn set_raw_mode(&mut self, _: bool) -> Result<(), WinterError> {
+        Ok(())
+    }
+    fn cursor_position(&mut self) -> Result<(u16, u16), WinterError> {
+        Ok((0, 0))
+    }
+}
+
+#[test]
+fn waker_sends_from_other_threads() {
+    let mut events: Events<u32> = Events::with_backend(WriterBackend::new(Vec::new(), 1, 1));
+    let waker = events.waker();
+    thread::spawn(move || {
+        assert!(waker.send(1));
+        assert!(waker.wake());
+    })
+    .join()
+    .unwrap();
+    assert_eq!(events.recv(), Message::User(1));
+    assert_eq!(events.recv(), Message::Wake);
+    assert_eq!(events.recv_timeout(Duration::ZERO), None);
+
+    let waker = events.waker();
+    drop(events);
+    assert!(!waker.send(2));
+}
+
+#[test]
+fn pause_stops_reading() {
+    let polls = Arc::new(AtomicUsize::new(0));
+    let events: Events<()> = Events::with_backend(Polls(polls.clone()));
+    thread::sleep(Duration::from_millis(20));
+    assert!(polls.load(Ordering::SeqCst) > 0);
+
+    events.pause();
+    let paused = polls.load(Ordering::SeqCst);
+    thread::sleep(Duration::from_millis(20));
+    assert_eq!(polls.load(Ordering::SeqCst), paused);
+
+    events.resume();
+    thread::sleep(Duration::from_millis(100));
+    assert!(polls.load(Ordering::SeqCst) > paused);
+}
+
+#[test]
+fn ticks_come_before_queued_messages() {
+    let mut events: Events<u32> = Events::with_backend(WriterBackend::new(Vec::new(), 1, 1))
+        .tick_rate(Duration::from_millis(10));
+    let waker = events.waker();
+    waker.send(1);
+    waker.send(2);
+    thread::sleep(Duration::from_millis(15));
+
+    assert_eq!(events.recv(), Message::Tick);
+    assert_eq!(events.recv(), Message::User(1));
+    assert_eq!(events.recv(), Message::User(2));
+    //Nothing else is queued, the next tick ends the wait.
+    assert_eq!(events.recv(), Message::Tick);
+}
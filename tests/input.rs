@@ -0,0 +1,569 @@
+use winter::*;
+
+fn key(code: KeyCode, state: u32) -> (Event, KeyState) {
+    (KeyEvent::new(code, KeyState(state)).into(), KeyState(state))
+}
+
+fn parse(bytes: &[u8]) -> Vec<(Event, KeyState)> {
+    let mut parser = InputParser::new();
+    parser.feed(bytes);
+    parser.flush();
+    std::iter::from_fn(|| parser.next()).collect()
+}
+
+#[test]
+fn split_sequences() {
+    let mut parser = InputParser::new();
+    let now = std::time::Instant::now();
+    for byte in b"\x1b[1;5A" {
+        assert_eq!(parser.next_at(now), None);
+        parser.feed_at(&[*byte], now);
+    }
+    assert_eq!(parser.next_at(now), Some(key(KeyCode::Up, CONTROL)));
+
+    //A character split between reads.
+    parser.feed_at(&"é".as_bytes()[..1], now);
+    assert_eq!(parser.next_at(now), None);
+    parser.feed_at(&"é".as_bytes()[1..], now);
+    assert_eq!(parser.next_at(now), Some(key(KeyCode::Char('é'), 0)));
+    assert_eq!(parser.timeout(now), None);
+}
+
+#[test]
+fn lone_escape() {
+    let mut parser = InputParser::new();
+    let now = std::time::Instant::now();
+    parser.feed_at(b"\x1b", now);
+    assert_eq!(parser.next_at(now), None);
+    assert_eq!(parser.timeout(now), Some(input::ESCAPE_TIMEOUT));
+
+    let later = now + input::ESCAPE_TIMEOUT;
+    assert_eq!(parser.timeout(later), Some(std::time::Duration::ZERO));
+    assert_eq!(parser.next_at(later), Some(key(KeyCode::Escape, 0)));
+    assert_eq!(parser.timeout(later), None);
+
+    //An escape followed by another is two key presses, not a sequence.
+    assert_eq!(
+        parse(b"\x1b\x1b"),
+        [key(KeyCode::Escape, 0), key(KeyCode::Escape, 0)]
+    );
+}
+
+#[test]
+fn ss3_and_csi() {
+    use KeyCode::*;
+    assert_eq!(
+        parse(b"\x1bOA\x1b[A\x1bOP\x1b[11~\x1b[15~\x1b[24~"),
+        [
+            key(Up, 0),
+            key(Up, 0),
+            key(Function(1), 0),
+            key(Function(1), 0),
+            key(Function(5), 0),
+            key(Function(12), 0),
+        ]
+    );
+    //Unknown sequences are skipped whole.
+    assert_eq!(
+        parse(b"\x1b[99zq\x1bO~q"),
+        [key(Char('q'), 0), key(Char('q'), 0)]
+    );
+}
+
+#[test]
+fn invalid_utf8() {
+    assert_eq!(
+        parse(b"\xffa\xc3(\xe2\x82"),
+        [key(KeyCode::Char('a'), 0), key(KeyCode::Char('('), 0),]
+    );
+}
+
+#[test]
+fn clipboard_reply() {
+    assert_eq!(
+        parse(b"\x1b]52;c;d2ludGVy\x07a"),
+        [
+            (Event::Clipboard("winter".into()), KeyState(0)),
+            key(KeyCode::Char('a'), 0)
+        ]
+    );
+    //Terminated with ST and split across reads.
+    let mut parser = InputParser::new();
+    parser.feed(b"\x1b]52;c;c25v");
+    assert_eq!(parser.next(), None);
+    parser.feed(b"dw==\x1b\\");
+    assert_eq!(
+        parser.next(),
+        Some((Event::Clipboard("snow".into()), KeyState(0)))
+    );
+
+    //A slow reply waits longer than a lone ESC would.
+    let mut parser = InputParser::new();
+    parser.feed(b"\x1b]52;c;d2lu");
+    let later = std::time::Instant::now() + input::ESCAPE_TIMEOUT * 2;
+    assert_eq!(parser.next_at(later), None);
+    parser.feed(b"dGVy\x07");
+    assert_eq!(
+        parser.next(),
+        Some((Event::Clipboard("winter".into()), KeyState(0)))
+    );
+
+    //Too long to keep, the rest is dropped up to the terminator instead of read as keys.
+    let mut parser = InputParser::new();
+    parser.feed(b"\x1b]52;c;");
+    parser.feed(&vec![b'A'; 1 << 20]);
+    parser.feed(b"AAAA\x07b");
+    assert_eq!(parser.next(), Some(key(KeyCode::Char('b'), 0)));
+    assert_eq!(parser.next(), None);
+
+    //Skipping stops once the terminal goes quiet without sending one.
+    parser.feed(b"\x1b]52;c;");
+    parser.feed(&vec![b'A'; 1 << 20]);
+    let later = std::time::Instant::now() + input::PASTE_TIMEOUT * 2;
+    assert_eq!(parser.next_at(later), None);
+    parser.feed(b"c");
+    assert_eq!(parser.next(), Some(key(KeyCode::Char('c'), 0)));
+}
+
+#[test]
+fn bracketed_paste() {
+    assert_eq!(
+        parse(b"\x1b[200~one\r\ntwo\rthree\x1b[A\x1b[201~x"),
+        [
+            (Event::Paste("one\ntwo\nthree\x1b[A".into()), KeyState(0)),
+            key(KeyCode::Char('x'), 0)
+        ]
+    );
+
+    //Longer than any escape sequence and split across reads.
+    let text = "winter ".repeat(100);
+    let mut parser = InputParser::new();
+    parser.feed(b"\x1b[200~");
+    parser.feed(text.as_bytes());
+    let later = std::time::Instant::now() + input::ESCAPE_TIMEOUT * 2;
+    assert_eq!(parser.next_at(later), None);
+    parser.feed(b"\x1b[201~");
+    assert_eq!(parser.next(), Some((Event::Paste(text), KeyState(0))));
+
+    //Only new bytes are searched for the end marker, it can still be split.
+    let mut parser = InputParser::new();
+    parser.feed(b"\x1b[200~abc\x1b[2");
+    parser.feed(b"01");
+    assert_eq!(parser.next(), None);
+    parser.feed(b"~");
+    assert_eq!(
+        parser.next(),
+        Some((Event::Paste("abc".into()), KeyState(0)))
+    );
+
+    //The end marker never arrives.
+    assert_eq!(
+        parse(b"\x1b[200~partial"),
+        [(Event::Paste("partial".into()), KeyState(0))]
+    );
+
+    //Too long to keep, what arrived is the paste and the rest is dropped.
+    let mut parser = InputParser::new();
+    parser.feed(b"\x1b[200~");
+    parser.feed(&vec![b'x'; 1 << 20]);
+    parser.feed(b"yy\x1b[20");
+    parser.feed(b"1~z");
+    let text = "x".repeat(1 << 20);
+    assert_eq!(parser.next(), Some((Event::Paste(text), KeyState(0))));
+    assert_eq!(parser.next(), Some(key(KeyCode::Char('z'), 0)));
+    assert_eq!(parser.next(), None);
+}
+
+#[test]
+fn focus_changes() {
+    assert_eq!(
+        parse(b"\x1b[O\x1b[I"),
+        [
+            (Event::FocusLost, KeyState(0)),
+            (Event::FocusGained, KeyState(0))
+        ]
+    );
+}
+
+fn mouse(kind: MouseKind, button: Option<MouseButton>, x: u16, y: u16) -> Event {
+    Event::Mouse(MouseEvent {
+        kind,
+        button,
+        x,
+        y,
+        modifiers: KeyState(0),
+    })
+}
+
+#[test]
+fn mouse_events() {
+    use MouseButton::*;
+    use MouseKind::*;
+    assert_eq!(
+        parse(b"\x1b[<2;1;1M\x1b[<34;2;1M\x1b[<2;2;1m\x1b[<35;5;5M")
+            .into_iter()
+            .map(|(event, _)| event)
+            .collect::<Vec<_>>(),
+        [
+            mouse(Down, Some(Right), 0, 0),
+            mouse(Drag, Some(Right), 1, 0),
+            mouse(Up, Some(Right), 1, 0),
+            mouse(Move, None, 4, 4),
+        ]
+    );
+    assert_eq!(
+        parse(b"\x1b[<64;1;1M\x1b[<65;1;1M\x1b[<66;1;1M\x1b[<67;1;1M")
+            .into_iter()
+            .map(|(event, _)| event)
+            .collect::<Vec<_>>(),
+        [
+            mouse(ScrollUp, None, 0, 0),
+            mouse(ScrollDown, None, 0, 0),
+            mouse(ScrollLeft, None, 0, 0),
+            mouse(ScrollRight, None, 0, 0),
+        ]
+    );
+
+    //Control + middle click.
+    let [(Event::Mouse(event), state)] = parse(b"\x1b[<17;3;4M")[..] else {
+        panic!();
+    };
+    assert_eq!((event.button, event.modifiers), (Some(Middle), state));
+    assert!(state.control());
+}
+
+#[test]
+fn double_click() {
+    use MouseKind::*;
+    let kinds = |parser: &mut InputParser| {
+        std::iter::from_fn(|| parser.next())
+            .map(|(event, _)| match event {
+                Event::Mouse(mouse) => mouse.kind,
+                event => panic!("{event:?}"),
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let mut parser = InputParser::new();
+    parser.feed(b"\x1b[<0;1;1M\x1b[<0;1;1m\x1b[<0;1;1M\x1b[<0;1;1m\x1b[<0;1;1M");
+    assert_eq!(kinds(&mut parser), [Down, Up, DoubleClick, Up, Down]);
+
+    //Too slow, or on another cell.
+    let now = std::time::Instant::now();
+    parser.feed_at(b"\x1b[<0;9;9M", now);
+    parser.feed_at(b"\x1b[<0;9;9M", now + input::DOUBLE_CLICK_TIME);
+    parser.feed_at(b"\x1b[<0;8;9M", now + input::DOUBLE_CLICK_TIME);
+    assert_eq!(kinds(&mut parser), [Down, Down, Down]);
+}
+
+#[test]
+fn navigation_keys() {
+    use KeyCode::*;
+    assert_eq!(
+        parse(b"\x1b[H\x1b[4~\x1b[2~\x1b[3;5~\x1b[5~\x1b[6;2~\x1bOF"),
+        [
+            key(Home, 0),
+            key(End, 0),
+            key(Insert, 0),
+            key(Delete, CONTROL),
+            key(PageUp, 0),
+            key(PageDown, SHIFT),
+            key(End, 0),
+        ]
+    );
+}
+
+#[test]
+fn text_and_modifiers() {
+    use KeyCode::*;
+    assert_eq!(
+        parse("aZ\x01é\x1bx\x1b\r\x1bOp\x1bOk".as_bytes()),
+        [
+            key(Char('a'), 0),
+            key(Char('Z'), SHIFT),
+            key(Char('a'), CONTROL),
+            key(Char('é'), 0),
+            key(Char('x'), ALT),
+            key(Enter, ALT),
+            key(Char('0'), 0),
+            key(Char('+'), 0),
+        ]
+    );
+}
+
+#[test]
+fn key_release_is_optional() {
+    let release: Event = KeyEvent {
+        kind: KeyKind::Release,
+        ..KeyEvent::new(KeyCode::Char('a'), KeyState(0))
+    }
+    .into();
+
+    let mut winter = Winter::with_backend(TestBackend::new(1, 1)).unwrap();
+    assert_eq!(winter.handle(release.clone(), KeyState(0)).unwrap(), None);
+
+    //`poll` skips over it to the next event instead of returning early.
+    let a: Event = KeyEvent::new(KeyCode::Char('a'), KeyState(0)).into();
+    winter.backend.push_event(release.clone(), KeyState(0));
+    winter.backend.push_event(a.clone(), KeyState(0));
+    assert_eq!(winter.poll().unwrap(), Some((a, KeyState(0))));
+
+    let options = WinterOptions::new().key_release(true);
+    let mut winter = Winter::with_backend_options(TestBackend::new(1, 1), options).unwrap();
+    assert_eq!(
+        winter.handle(release.clone(), KeyState(0)).unwrap(),
+        Some((release, KeyState(0)))
+    );
+}
+
+#[test]
+fn kitty_keys() {
+    use KeyCode::*;
+    let release = |code, state| {
+        let key = KeyEvent {
+            kind: KeyKind::Release,
+            ..KeyEvent::new(code, KeyState(state))
+        };
+        (key.into(), KeyState(state))
+    };
+    assert_eq!(
+        parse(b"\t\x1b[105;5u\x1b[13;2u\x1b[27u\x1b[97;9u\x1b[49:33;2u\x1b[97:65;6u"),
+        [
+            key(Tab, 0),
+            key(Char('i'), CONTROL),
+            key(Enter, SHIFT),
+            key(Escape, 0),
+            key(Char('a'), SUPER),
+            key(Char('!'), SHIFT),
+            key(Char('A'), CONTROL | SHIFT),
+        ]
+    );
+    assert_eq!(
+        parse(b"\x1b[97;1:3u\x1b[1;5:3A\x1b[3;1:2~\x1b[57399;129u\x1b[57428u\x1b[?7u"),
+        [
+            release(Char('a'), 0),
+            release(Up, CONTROL),
+            (
+                KeyEvent {
+                    kind: KeyKind::Repeat,
+                    ..KeyEvent::new(Delete, KeyState(0))
+                }
+                .into(),
+                KeyState(0)
+            ),
+            key(Char('0'), NUM_LOCK),
+            key(Media(MediaKey::Play), 0),
+        ]
+    );
+
+    //Lock keys don't get in the way of bindings or quitting.
+    let binding: keymap::KeyBinding = "ctrl+c".parse().unwrap();
+    let (event, state) = key(Char('c'), CONTROL | CAPS_LOCK);
+    assert!(binding.matches(&event, state));
+}
+
+#[test]
+fn kitty_keyboard_negotiation() {
+    let mut winter = Winter::with_backend(TestBackend::new(1, 1)).unwrap();
+    assert!(winter.backend.kitty_keyboard);
+    winter.suspend().unwrap();
+    assert!(!winter.backend.kitty_keyboard);
+    winter.resume().unwrap();
+    assert!(winter.backend.kitty_keyboard);
+
+    let options = WinterOptions::new().kitty_keyboard(false);
+    let winter = Winter::with_backend_options(TestBackend::new(1, 1), options).unwrap();
+    assert!(!winter.backend.kitty_keyboard);
+
+    let mut output = Vec::new();
+    push_keyboard_flags(&mut output, KEYBOARD_FLAGS);
+    pop_keyboard_flags(&mut output);
+    assert_eq!(output, b"\x1b[>7u\x1b[<u");
+}
+
+#[test]
+fn kitty_keyboard_flags_follow_the_screen() {
+    let options = WinterOptions::new().synchronized_output(false);
+    let backend = WriterBackend::new(Vec::new(), 2, 2);
+    let mut winter = Winter::with_backend_options(backend, options).unwrap();
+    winter.backend.writer.clear();
+
+    //Popped from the alternate screen's stack and pushed on the main one.
+    winter.set_alternate_screen(false).unwrap();
+    let output = String::from_utf8(std::mem::take(&mut winter.backend.writer)).unwrap();
+    assert!(
+        output.starts_with("\x1b[<u\x1b[?1049l\x1b[>7u"),
+        "{output:?}"
+    );
+
+    //Resumed without being suspended, the flags are popped before they are pushed again.
+    winter.handle(Event::Resume, KeyState(0)).unwrap();
+    let output = String::from_utf8(std::mem::take(&mut winter.backend.writer)).unwrap();
+    assert_eq!(output.matches("\x1b[<u").count(), 1, "{output:?}");
+    assert_eq!(output.matches("\x1b[>7u").count(), 1, "{output:?}");
+    assert!(
+        output.find("\x1b[<u") < output.find("\x1b[>7u"),
+        "{output:?}"
+    );
+}
+
+#[test]
+fn key_bindings() {
+    use keymap::KeyBinding;
+    for (text, code, state) in [
+        ("ctrl+c", KeyCode::Char('c'), CONTROL),
+        ("shift+tab", KeyCode::Tab, SHIFT),
+        ("alt+enter", KeyCode::Enter, ALT),
+        ("f5", KeyCode::Function(5), 0),
+        ("Ctrl+Alt+Delete", KeyCode::Delete, CONTROL | ALT),
+        ("ctrl++", KeyCode::Char('+'), CONTROL),
+        ("G", KeyCode::Char('g'), SHIFT),
+        ("?", KeyCode::Char('?'), 0),
+        (
+            "super+hyper+meta+s",
+            KeyCode::Char('s'),
+            SUPER | HYPER | META,
+        ),
+    ] {
+        let binding: KeyBinding = text.parse().unwrap();
+        assert_eq!(binding, KeyBinding::new(code, KeyState(state)), "{text}");
+        assert_eq!(binding.to_string().parse::<KeyBinding>().unwrap(), binding);
+    }
+    for text in ["", "ctrl+", "cmd+a", "f25", "enterr"] {
+        assert!(text.parse::<KeyBinding>().is_err(), "{text}");
+    }
+
+    //Shift is part of the character on some platforms and not on others.
+    let binding: KeyBinding = "?".parse().unwrap();
+    let (event, _) = key(KeyCode::Char('?'), SHIFT);
+    assert!(binding.matches(&event, KeyState(SHIFT)));
+    assert!(binding.matches(&event, KeyState(0)));
+    assert!(!"ctrl+?"
+        .parse::<KeyBinding>()
+        .unwrap()
+        .matches(&event, KeyState(0)));
+}
+
+#[test]
+fn keymap_config() {
+    let mut keymap =
+        keymap::Keymap::from_config("quit = ctrl+c q\n\n# Comment\nplay = space\n").unwrap();
+    assert_eq!(keymap.to_config(), "quit = ctrl+c q\nplay = space\n");
+
+    let (q, state) = key(KeyCode::Char('q'), 0);
+    assert_eq!(keymap.get(&q, state), Some(&String::from("quit")));
+
+    //Errors leave the keymap as it was.
+    for (config, line) in [("quit = q\nplay", 2), ("play = ctrl+nope", 1)] {
+        match keymap.load(config) {
+            Err(WinterError::InvalidKeymap { line: l, .. }) => assert_eq!(l, line),
+            result => panic!("{result:?}"),
+        }
+    }
+    keymap.load("play =\n").unwrap();
+    assert_eq!(keymap.to_config(), "quit = ctrl+c q\n");
+}
+
+#[test]
+fn chords() {
+    use keymap::{chord, ChordEvent::*};
+    let mut chords = keymap::chords()
+        .bind(chord("d d").unwrap(), "delete")
+        .bind(chord("<space> f s").unwrap(), "save")
+        .bind(chord("<space> f o").unwrap(), "open")
+        .bind(chord("g").unwrap(), "next")
+        .bind(chord("g g").unwrap(), "top");
+    let mut feed = |keys: &str| {
+        for c in keys.chars() {
+            let (event, state) = key(KeyCode::Char(c), 0);
+            chords.feed(event, state);
+        }
+        std::iter::from_fn(|| chords.next()).collect::<Vec<_>>()
+    };
+
+    assert_eq!(feed("dd"), [Action("delete")]);
+    //`d x` isn't a chord, both keys are handed back.
+    assert_eq!(
+        feed("dx"),
+        [
+            Unmatched(key(KeyCode::Char('d'), 0).0, KeyState(0)),
+            Unmatched(key(KeyCode::Char('x'), 0).0, KeyState(0))
+        ]
+    );
+    //The shorter chord fires once the longer one is broken, then the key starts over.
+    assert_eq!(feed("gdd"), [Action("next"), Action("delete")]);
+    assert_eq!(feed(" f"), []);
+
+    let hints: Vec<_> = chords
+        .continuations()
+        .map(|(keys, action)| (keys[0].to_string(), *action))
+        .collect();
+    assert_eq!(
+        hints,
+        [("s".to_string(), "save"), ("o".to_string(), "open")]
+    );
+
+    //Other events don't interrupt a chord, they wait behind it to keep the order.
+    chords.feed(Event::Resize(10, 10), KeyState(0));
+    assert_eq!(chords.next(), None);
+    let (o, state) = key(KeyCode::Char('o'), 0);
+    chords.feed(o, state);
+    assert_eq!(chords.next(), Some(Action("open")));
+    assert_eq!(
+        chords.next(),
+        Some(Unmatched(Event::Resize(10, 10), KeyState(0)))
+    );
+    assert!(chords.pending().is_empty());
+}
+
+#[test]
+fn broken_chords_keep_their_bound_prefix() {
+    use keymap::{chord, ChordEvent::*};
+    let mut chords = keymap::chords()
+        .bind(chord("g").unwrap(), "next")
+        .bind(chord("g g x").unwrap(), "close");
+    for c in "ggy".chars() {
+        let (event, state) = key(KeyCode::Char(c), 0);
+        chords.feed(event, state);
+    }
+    let (y, state) = key(KeyCode::Char('y'), 0);
+    assert_eq!(
+        std::iter::from_fn(|| chords.next()).collect::<Vec<_>>(),
+        [Action("next"), Action("next"), Unmatched(y, state)]
+    );
+
+    //The same when the keys time out instead.
+    for c in "gg".chars() {
+        let (event, state) = key(KeyCode::Char(c), 0);
+        chords.feed(event, state);
+    }
+    chords.flush();
+    assert_eq!(
+        std::iter::from_fn(|| chords.next()).collect::<Vec<_>>(),
+        [Action("next"), Action("next")]
+    );
+}
+
+#[test]
+fn chord_timeout() {
+    use keymap::{chord, ChordEvent::*};
+    let mut chords = keymap::chords()
+        .bind(chord("d d").unwrap(), "delete")
+        .chord_timeout(std::time::Duration::from_millis(100));
+    let now = std::time::Instant::now();
+    let (d, state) = key(KeyCode::Char('d'), 0);
+
+    chords.feed_at(d.clone(), state, now);
+    assert_eq!(
+        chords.timeout(now),
+        Some(std::time::Duration::from_millis(100))
+    );
+    let later = now + std::time::Duration::from_millis(100);
+    assert_eq!(chords.next_at(later), Some(Unmatched(d.clone(), state)));
+    assert_eq!(chords.timeout(later), None);
+
+    //Reset, the next `d` starts a new chord.
+    chords.feed_at(d.clone(), state, later);
+    chords.feed_at(d, state, later);
+    assert_eq!(chords.next_at(later), Some(Action("delete")));
+}
@@ -0,0 +1,289 @@
+use winter::*;
+
+fn items(count: usize) -> Vec<Line<'static>> {
+    (0..count).map(|i| lines!(format!("Item {i}"))).collect()
+}
+
+#[test]
+fn list_scroll_uses_scroll_region() {
+    let items = items(30);
+    let mut winter = Winter::with_backend(TestBackend::new(12, 8)).unwrap();
+    let draw = |winter: &mut Winter<TestBackend>, index: usize| {
+        let area = winter.viewport;
+        list(&items)
+            .block(block())
+            .symbol(">")
+            .draw(area, winter.buffer(), Some(index));
+        winter.draw().unwrap();
+    };
+
+    draw(&mut winter, 5);
+    assert_eq!(winter.stats.scroll, None);
+
+    //The list moves up by one, the borders stay where they are.
+    draw(&mut winter, 6);
+    assert_eq!(
+        winter.stats.scroll,
+        Some(buffer::Scroll {
+            top: 1,
+            bottom: 7,
+            amount: 1
+        })
+    );
+
+    let expected = {
+        let mut buf = Buffer::empty(Rect::new(0, 0, 12, 8));
+        list(&items)
+            .block(block())
+            .symbol(">")
+            .draw(buf.area, &mut buf, Some(6));
+        buf
+    };
+    assert_buffer_eq!(winter.backend.screen, expected);
+}
+
+#[test]
+fn narrow_scrolls_are_redrawn() {
+    let mut winter = Winter::with_backend(TestBackend::new(1, 4)).unwrap();
+    *winter.buffer() = Buffer::with_lines(["a", "b", "c", "d"]);
+    winter.draw().unwrap();
+
+    *winter.buffer() = Buffer::with_lines(["b", "c", "d", "e"]);
+    winter.draw().unwrap();
+    assert_eq!(winter.stats.scroll, None);
+    assert_eq!(winter.backend.lines(), ["b", "c", "d", "e"]);
+}
+
+#[test]
+fn cursor_is_placed_after_the_frame() {
+    let mut winter = Winter::with_backend(TestBackend::new(10, 2)).unwrap();
+    lines!("Name: ").draw(winter.viewport, winter.buffer());
+    winter.set_cursor(Some((6, 0)));
+    winter.draw().unwrap();
+    assert_eq!(winter.backend.cursor, (6, 0));
+    assert!(winter.backend.cursor_visible);
+
+    winter.set_cursor(None);
+    winter.draw().unwrap();
+    assert!(!winter.backend.cursor_visible);
+
+    //Outside of the viewport it stays hidden.
+    winter.set_cursor(Some((20, 0)));
+    winter.draw().unwrap();
+    assert!(!winter.backend.cursor_visible);
+}
+
+#[test]
+fn links_are_wrapped_in_osc_8() {
+    let mut winter = Winter::with_backend(TestBackend::new(10, 1)).unwrap();
+    lines!("see ", "docs".link("https://example.com")).draw(winter.viewport, winter.buffer());
+    winter.draw().unwrap();
+    assert_eq!(
+        winter.backend.screen.snapshot(),
+        "see docs  \n---\ny=0 x=4..8 link=https://example.com\n"
+    );
+
+    let mut output = Vec::new();
+    buffer::draw(
+        &mut output,
+        Buffer::empty(winter.viewport).diff(&winter.backend.screen),
+    );
+    let output = String::from_utf8(output).unwrap();
+    assert!(
+        output.contains("\x1b]8;;https://example.com\x1b\\docs\x1b]8;;\x1b\\"),
+        "{output:?}"
+    );
+
+    //A target can't smuggle in its own escape sequences.
+    let mut output = Vec::new();
+    start_hyperlink(&mut output, "https://x.com/\x1b]0;owned\x07\u{9c}");
+    assert_eq!(output, b"\x1b]8;;https://x.com/]0;owned\x1b\\");
+
+    //Only the link changed, the cells are drawn again.
+    lines!("see ", "docs".link("https://example.org")).draw(winter.viewport, winter.buffer());
+    winter.draw().unwrap();
+    assert_eq!(winter.stats.cells, 4);
+}
+
+#[test]
+fn flash_restores_the_screen() {
+    let mut winter = Winter::with_backend(TestBackend::new(5, 1)).unwrap();
+    lines!("hello").draw(winter.viewport, winter.buffer());
+    winter.draw().unwrap();
+    lines!("next").draw(winter.viewport, winter.buffer());
+
+    winter.flash(std::time::Duration::ZERO).unwrap();
+    assert_buffer_eq!(winter.backend.screen, Buffer::with_lines(["hello"]));
+    assert_eq!(winter.buffer().to_lines(), ["next "]);
+}
+
+#[test]
+fn flash_lasts_until_a_later_draw() {
+    let mut winter = Winter::with_backend(TestBackend::new(5, 1)).unwrap();
+    lines!("hello").draw(winter.viewport, winter.buffer());
+    winter.draw().unwrap();
+
+    //Returns right away, the frame stays inverted while the app keeps drawing.
+    winter.flash(std::time::Duration::from_secs(60)).unwrap();
+    let mut inverted = Buffer::with_lines(["hello"]);
+    inverted.set_style(inverted.area, Style::default().invert());
+    assert_buffer_eq!(winter.backend.screen, inverted);
+    lines!("hello").draw(winter.viewport, winter.buffer());
+    winter.draw().unwrap();
+    assert_buffer_eq!(winter.backend.screen, inverted);
+
+    winter.flash_until = Some(std::time::Instant::now());
+    lines!("hello").draw(winter.viewport, winter.buffer());
+    winter.draw().unwrap();
+    assert_buffer_eq!(winter.backend.screen, Buffer::with_lines(["hello"]));
+    assert_eq!(winter.flash_until, None);
+}
+
+fn shell(lines: [&str; 5], cursor: u16) -> TestBackend {
+    let mut backend = TestBackend::new(5, 5);
+    backend.screen = Buffer::with_lines(lines.map(|line| format!("{line:5}")));
+    backend.cursor = (0, cursor);
+    backend
+}
+
+#[test]
+fn inline_viewport_below_the_cursor() {
+    let backend = shell(["$ ls", "", "", "", ""], 1);
+    let options = WinterOptions::new().inline(2);
+    let mut winter = Winter::with_backend_options(backend, options).unwrap();
+    assert_eq!(winter.viewport, Rect::new(0, 1, 5, 2));
+    assert!(!winter.backend.alternate_screen);
+
+    lines!("ui").draw(winter.viewport, winter.buffer());
+    winter.draw().unwrap();
+    assert_eq!(
+        winter.backend.lines(),
+        ["$ ls ", "ui   ", "     ", "     ", "     "]
+    );
+
+    //The prompt goes below the last frame.
+    winter.suspend().unwrap();
+    assert_eq!(winter.backend.cursor, (0, 3));
+}
+
+#[test]
+fn inline_viewport_scrolls_to_fit() {
+    let backend = shell(["$ a", "1", "$ b", "2", "$ c"], 4);
+    let options = WinterOptions::new().inline(3);
+    let winter = Winter::with_backend_options(backend, options).unwrap();
+    assert_eq!(winter.viewport, Rect::new(0, 2, 5, 3));
+    assert_eq!(
+        winter.backend.lines(),
+        ["$ b  ", "2    ", "     ", "     ", "     "]
+    );
+
+    //Never taller than the screen.
+    let options = WinterOptions::new().inline(9);
+    let winter = Winter::with_backend_options(shell([""; 5], 0), options).unwrap();
+    assert_eq!(winter.viewport, Rect::new(0, 0, 5, 5));
+}
+
+#[test]
+fn insert_before_pushes_the_viewport_down() {
+    let options = WinterOptions::new().inline(2);
+    let mut winter = Winter::with_backend_options(shell([""; 5], 2), options).unwrap();
+    assert_eq!(winter.viewport, Rect::new(0, 2, 5, 2));
+
+    winter
+        .insert_before(2, |buf| {
+            lines!("one").draw(Rect::new(0, 0, 5, 1), buf);
+            lines!("two").draw(Rect::new(0, 1, 5, 1), buf);
+        })
+        .unwrap();
+    assert_eq!(winter.viewport, Rect::new(0, 3, 5, 2));
+    lines!("ui").draw(winter.viewport, winter.buffer());
+    winter.draw().unwrap();
+    assert_eq!(
+        winter.backend.lines(),
+        ["     ", "one  ", "two  ", "ui   ", "     "]
+    );
+}
+
+#[test]
+fn render_stats_count_the_frame() {
+    let next = Buffer::with_lines(["ab d"]);
+    let diff = Buffer::empty(next.area).diff(&next);
+    let mut output = Vec::new();
+    let stats = buffer::draw(&mut output, diff);
+    assert_eq!(stats.cells, 3);
+    assert_eq!(stats.bytes, output.len());
+    //To the start, to `a`, then over the unchanged cell to `d`.
+    assert_eq!(stats.cursor_moves, 3);
+
+    let mut winter = Winter::with_backend(TestBackend::new(4, 1)).unwrap();
+    lines!("ab d").draw(winter.viewport, winter.buffer());
+    winter.draw().unwrap();
+    assert_eq!(winter.stats.cells, 3);
+
+    //Nothing changed, nothing is sent.
+    lines!("ab d").draw(winter.viewport, winter.buffer());
+    winter.draw().unwrap();
+    assert_eq!(winter.stats.cells, 0);
+
+    //Everything sent for the frame counts, not just the cells.
+    let options = WinterOptions::new().synchronized_output(true);
+    let backend = WriterBackend::new(Vec::new(), 4, 1);
+    let mut winter = Winter::with_backend_options(backend, options).unwrap();
+    winter.backend.writer.clear();
+    lines!("ab d").draw(winter.viewport, winter.buffer());
+    winter.set_cursor(Some((1, 0)));
+    winter.draw().unwrap();
+    let frame = std::mem::take(&mut winter.backend.writer);
+    assert!(frame.starts_with(b"\x1b[?2026h"));
+    assert_eq!(winter.stats.bytes, frame.len());
+}
+
+#[test]
+fn find_scroll_needs_shifted_rows() {
+    let rows = |range: std::ops::Range<u16>| range.map(|i| format!("Row number {i:>10}"));
+    let previous = Buffer::with_lines(rows(0..6));
+    assert_eq!(previous.find_scroll(&previous.clone()), None);
+
+    //Scrolling back up shifts the other way.
+    let scroll = Buffer::with_lines(rows(2..8)).find_scroll(&previous);
+    assert_eq!(
+        scroll,
+        Some(buffer::Scroll {
+            top: 0,
+            bottom: 6,
+            amount: -2
+        })
+    );
+
+    //Unrelated content has nothing to reuse.
+    let other = Buffer::with_lines(rows(10..16));
+    assert_eq!(previous.find_scroll(&other), None);
+
+    //Narrow rows don't save enough to pay for the scroll region.
+    let narrow = |range: std::ops::Range<u16>| range.map(|i| format!("{i}"));
+    let previous = Buffer::with_lines(narrow(0..6));
+    assert_eq!(
+        previous.find_scroll(&Buffer::with_lines(narrow(1..7))),
+        None
+    );
+}
+
+#[test]
+fn scroll_writes_a_scroll_region() {
+    let mut backend = WriterBackend::new(Vec::new(), 5, 8);
+    backend.scroll(buffer::Scroll {
+        top: 1,
+        bottom: 7,
+        amount: 2,
+    });
+    backend.scroll(buffer::Scroll {
+        top: 0,
+        bottom: 8,
+        amount: -1,
+    });
+    std::io::Write::flush(&mut backend).unwrap();
+    assert_eq!(
+        String::from_utf8(backend.writer).unwrap(),
+        "\x1b[2;7r\x1b[2S\x1b[r\x1b[1;8r\x1b[1T\x1b[r"
+    );
+}
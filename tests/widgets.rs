@@ -0,0 +1,164 @@
+use winter::*;
+
+fn render(width: u16, height: u16, draw: impl FnOnce(Rect, &mut Buffer)) -> Buffer {
+    let mut buf = Buffer::empty(Rect::new(0, 0, width, height));
+    draw(buf.area, &mut buf);
+    buf
+}
+
+#[test]
+fn block_with_title() {
+    let buf = render(10, 3, |area, buf| {
+        block().title("Title").draw(area, buf);
+    });
+    assert_buffer_eq!(
+        buf,
+        Buffer::with_lines(["╭Title───╮", "│        │", "╰────────╯"])
+    );
+    assert_snapshot!("block", buf);
+}
+
+#[test]
+fn block_border_types() {
+    let buf = render(12, 3, |area, buf| {
+        let chunks = layout(area, Horizontal, &[Length(4), Length(4), Length(4)]);
+        block().border_type(Plain).draw(chunks[0], buf);
+        block().border_type(Double).draw(chunks[1], buf);
+        block()
+            .border_type(Thick)
+            .borders(LEFT | RIGHT)
+            .draw(chunks[2], buf);
+    });
+    assert_buffer_eq!(
+        buf,
+        Buffer::with_lines(["┌──┐╔══╗┃  ┃", "│  │║  ║┃  ┃", "└──┘╚══╝┃  ┃"])
+    );
+}
+
+#[test]
+fn list_with_selection() {
+    let items = [lines!("One"), lines!("Two"), lines!("Three")];
+    let buf = render(10, 5, |area, buf| {
+        list(&items)
+            .block(block().title("List"))
+            .symbol("> ")
+            .selection_style(fg(Black).bg(White))
+            .draw(area, buf, Some(1));
+    });
+
+    let mut expected = Buffer::with_lines([
+        "╭List────╮",
+        "│  One   │",
+        "│> Two   │",
+        "│  Three │",
+        "╰────────╯",
+    ]);
+    expected.set_style(Rect::new(1, 2, 8, 1), fg(Black).bg(White));
+    assert_buffer_eq!(buf, expected);
+    assert_snapshot!("list", buf);
+}
+
+#[test]
+fn list_scrolls_to_selection() {
+    let items = [
+        lines!("1"),
+        lines!("2"),
+        lines!("3"),
+        lines!("4"),
+        lines!("5"),
+    ];
+    let buf = render(4, 2, |area, buf| {
+        list(&items).symbol(">").draw(area, buf, Some(4));
+    });
+    assert_buffer_eq!(buf, Buffer::with_lines([" 4  ", ">5  "]));
+}
+
+#[test]
+fn table_with_header() {
+    let widths = [Length(5), Length(5)];
+    let rows = [row!["a", "1"], row!["b", "2"], row!["c".bold(), "3"]];
+    let buf = render(14, 6, |area, buf| {
+        table(rows, &widths)
+            .header(header!["Name", "Num"])
+            .block(block())
+            .symbol("> ")
+            .spacing(1)
+            .draw(area, buf, Some(2));
+    });
+
+    //The header has a blank margin row and the selection is scrolled into view.
+    let mut expected = Buffer::with_lines([
+        "╭────────────╮",
+        "│  Name  Num │",
+        "│            │",
+        "│  b     2   │",
+        "│> c     3   │",
+        "╰────────────╯",
+    ]);
+    expected.set_style(Rect::new(3, 4, 5, 1), bold());
+    assert_buffer_eq!(buf, expected);
+    assert_snapshot!("table", buf);
+}
+
+#[test]
+fn gauge_half_filled() {
+    let buf = render(10, 3, |area, buf| {
+        guage(Some(block()), 0.5, "50%".into(), style().bg(Blue), style()).draw(area, buf);
+    });
+
+    //The label is black over the filled part.
+    let mut expected = Buffer::with_lines(["╭────────╮", "│  50%   │", "╰────────╯"]);
+    expected.set_style(Rect::new(1, 1, 4, 1), bg(Blue));
+    expected.set_style(Rect::new(3, 1, 2, 1), fg(Black).bg(Blue));
+    assert_buffer_eq!(buf, expected);
+    assert_snapshot!("gauge", buf);
+}
+
+#[test]
+fn snapshot_round_trip() {
+    let mut buf = Buffer::with_lines(["wide 世界", "plain"]);
+    buf.set_style(Rect::new(0, 1, 5, 1), fg(Red).bold().italic());
+    let snapshot = buf.snapshot();
+    assert_eq!(
+        snapshot,
+        "wide 世界\nplain    \n---\ny=1 x=0..5 fg=Red modifier=BOLD|ITALIC\n"
+    );
+    assert_buffer_eq!(Buffer::from_snapshot(&snapshot).unwrap(), buf);
+}
+
+#[test]
+fn invalid_snapshots() {
+    for snapshot in [
+        "ab\n",
+        "ab\n---\ny=0 x=0..2 fg=Pink\n",
+        "ab\n---\ny=0 x=0..2 size=2\n",
+        "ab\n---\ny=0 x=1..3 fg=Red\n",
+    ] {
+        assert!(Buffer::from_snapshot(snapshot).is_err(), "{snapshot:?}");
+    }
+    assert!(matches!(
+        "Pink".parse::<Color>(),
+        Err(WinterError::InvalidColor(color)) if color == "Pink"
+    ));
+}
+
+#[test]
+fn compare_marks_differences() {
+    let mut actual = Buffer::with_lines(["abc"]);
+    actual.get_mut(1, 0).unwrap().set_char('x').set_fg(Red);
+    actual.get_mut(2, 0).unwrap().set_bg(Blue);
+
+    let report = buffer::compare(&Buffer::with_lines(["abc"]), &actual).unwrap();
+    assert!(report.contains("\"abc\"   \"axc\"    *B"), "{report}");
+    assert!(report.contains("(1, 0) symbol \"b\" != \"x\""), "{report}");
+    assert!(report.contains("(1, 0) fg Reset != Red"), "{report}");
+    assert!(report.contains("(2, 0) bg Reset != Blue"), "{report}");
+    assert!(buffer::compare(&actual, &actual.clone()).is_none());
+}
+
+#[test]
+#[should_panic(expected = "does not exist")]
+fn missing_snapshots_fail() {
+    let buf = Buffer::with_lines(vec!["x"]);
+    buffer::assert_snapshot("tests/snapshots/missing.snap", &buf);
+}